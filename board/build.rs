@@ -17,12 +17,68 @@ fn create_runtime(family: imxrt_rt::Family, flash_size: usize) -> imxrt_rt::Runt
     }
 }
 
+/// Configures a Teensy 4 runtime that uses the less common layout features.
+///
+/// The `__layout` board adds enough code to spill out of ITCM.
+fn layout_runtime() {
+    const BOARD_RODATA: &str = "*libboard*:*(.rodata .rodata.*)";
+    imxrt_rt::RuntimeBuilder::from_flexspi(imxrt_rt::Family::Imxrt1060, 1984 * 1024)
+        .flexram_banks(imxrt_rt::FlexRamBanks {
+            ocram: 11,
+            dtcm: 4,
+            itcm: 1,
+        })
+        .text(imxrt_rt::Placement::Prefer(imxrt_rt::Memory::Itcm).fallback(imxrt_rt::Memory::Flash))
+        .place_input(BOARD_RODATA, imxrt_rt::Memory::Ocram)
+        .align(imxrt_rt::Section::Custom(BOARD_RODATA.into()), 64)
+        .fixed_section(
+            ".mailbox",
+            0x2001_F000,
+            0x1000,
+            imxrt_rt::FixedSectionKind::NoLoad,
+        )
+        .fixed_section(
+            ".shared",
+            0x2001_E000,
+            0x100,
+            imxrt_rt::FixedSectionKind::Initialized,
+        )
+        .defer(imxrt_rt::Section::Custom(BOARD_RODATA.into()))
+        .defer(imxrt_rt::Section::Text)
+        .integrity_check(true)
+        .boot_timings(true)
+        .watchdog(
+            imxrt_rt::Watchdog::Wdog1,
+            imxrt_rt::WatchdogMode::Enable { timeout_ms: 2000 },
+        )
+        .watchdog(imxrt_rt::Watchdog::Wdog2, imxrt_rt::WatchdogMode::Disable)
+        .watchdog(
+            imxrt_rt::Watchdog::Rtwdog,
+            imxrt_rt::WatchdogMode::Enable { timeout_ms: 10_000 },
+        )
+        .core_clock_mhz(600)
+        .build()
+        .unwrap();
+}
+
 /// Configures the runtime for a variety of boards.
 ///
 /// Note that some automated tests may check these runtimes. Feel free to change
 /// values and observe how they might affect the tests.
 fn main() {
     let features = extract_features();
+    // These dummy boards extend the teensy4 board, so they're handled first.
+    if features.contains("__layout") {
+        layout_runtime();
+        return;
+    }
+    if features.contains("__compress") {
+        imxrt_rt::RuntimeBuilder::from_flexspi(imxrt_rt::Family::Imxrt1060, 1984 * 1024)
+            .compress(4 * 1024 * 1024)
+            .build()
+            .unwrap();
+        return;
+    }
     for feature in features {
         match feature.as_str() {
             "teensy4" => {
@@ -32,6 +88,16 @@ fn main() {
                         dtcm: 12,
                         itcm: 4,
                     })
+                    .restrict_tcm_sizes(true)
+                    .reset_reason(true)
+                    .flexspi_ahb(imxrt_rt::FlexSpiAhb {
+                        prefetch: true,
+                        buffers: vec![imxrt_rt::AhbRxBuffer {
+                            master_id: 0,
+                            size: 1024,
+                            prefetch: true,
+                        }],
+                    })
                     .heap_size(1024)
                     .text(imxrt_rt::Memory::Flash)
                     .rodata(imxrt_rt::Memory::Dtcm)
@@ -50,12 +116,32 @@ fn main() {
             )
             .heap_size(1024)
             .rodata(imxrt_rt::Memory::Flash)
+            .boot_timings(true)
+            .integrity_check(true)
+            .watchdog(
+                imxrt_rt::Watchdog::Wdog1,
+                imxrt_rt::WatchdogMode::Enable { timeout_ms: 2000 },
+            )
+            .watchdog(imxrt_rt::Watchdog::Wdog2, imxrt_rt::WatchdogMode::Disable)
+            .watchdog(
+                imxrt_rt::Watchdog::Rtwdog,
+                imxrt_rt::WatchdogMode::Enable { timeout_ms: 10_000 },
+            )
+            .init_arrays(true)
+            .interrupts(240)
+            .relocated_vectors(imxrt_rt::Memory::Dtcm)
             .stack_size_env_override("BOARD_STACK")
             .heap_size_env_override("BOARD_HEAP")
             .build()
             .unwrap(),
             "imxrt1170evk_cm7" => create_runtime(imxrt_rt::Family::Imxrt1170, 8 * 1024 * 1024)
                 .rodata(imxrt_rt::Memory::Dtcm)
+                .align(imxrt_rt::Section::Bss, 32)
+                .align(imxrt_rt::Section::Uninit, 32)
+                .retained_size(256)
+                .crash_record(true)
+                .reset_reason(true)
+                .unwind_tables(imxrt_rt::Memory::Flash)
                 .stack_size_env_override("BOARD_STACK")
                 .heap_size_env_override("BOARD_HEAP")
                 .build()
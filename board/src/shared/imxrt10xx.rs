@@ -5,15 +5,13 @@ pub(crate) fn prepare_pit(timer_delay_microseconds: u32) -> Option<crate::Pit> {
     #[cfg(feature = "rtic")]
     {
         unsafe extern "C" {
-            // Not actually mut in cortex-m. But, no one is reading it...
-            static __INTERRUPTS: [core::cell::UnsafeCell<unsafe extern "C" fn()>; 240];
             fn PIT();
         }
-        unsafe {
-            __INTERRUPTS[crate::ral::interrupt::PIT as usize]
-                .get()
-                .write_volatile(PIT);
+        extern "C" fn pit() {
+            // Safety: RTIC's handler expects to run in the PIT interrupt.
+            unsafe { PIT() }
         }
+        imxrt_rt::register_interrupt(crate::ral::interrupt::PIT as u16, pit).ok()?;
     }
     let ccm = unsafe { ral::ccm::CCM::instance() };
     // Disable the PIT clock gate while we change the clock...
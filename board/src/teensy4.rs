@@ -28,6 +28,9 @@ pub fn prepare(timer_delay_microseconds: u32) -> Option<crate::Resources> {
         DSE: DSE_7_R0_7
     );
 
+    #[cfg(feature = "__layout")]
+    layout::touch();
+
     let pit = crate::prepare_pit(timer_delay_microseconds)?;
     let gpio2 = unsafe { ral::gpio::GPIO2::instance() };
     Some(crate::Resources {
@@ -50,3 +53,41 @@ pub static DEVICE_CONFIGURATION_DATA: [u8; 8] = [0xD2, 0x00, 0x08, 0x41, 0xC0, 0
 #[unsafe(no_mangle)]
 #[used]
 pub static DEVICE_CONFIGURATION_DATA: [u8; 7] = [0xD2, 0x00, 0x08, 0x41, 0xC0, 0x00, 0x04];
+
+/// Dummy sections for testing memory layouts.
+#[cfg(feature = "__layout")]
+mod layout {
+    // With the rest of the program, more code than fits in the board's 32KiB of ITCM.
+    #[cfg(target_arch = "arm")]
+    core::arch::global_asm! {
+        ".section .text.layout_filler,\"ax\",%progbits",
+        ".global __layout_filler",
+        ".type __layout_filler,%function",
+        ".thumb_func",
+        "__layout_filler:",
+        "bx lr",
+        ".space 30 * 1024",
+    }
+
+    #[cfg(target_arch = "arm")]
+    unsafe extern "C" {
+        fn __layout_filler();
+    }
+
+    #[unsafe(link_section = ".mailbox")]
+    #[used]
+    static mut MAILBOX: [u32; 4] = [0; 4];
+
+    #[unsafe(link_section = ".shared")]
+    #[used]
+    static mut SHARED: [u32; 4] = [1, 2, 3, 4];
+
+    /// Keep the filler, and the deferred section loader, in the image.
+    pub fn touch() {
+        #[cfg(target_arch = "arm")]
+        unsafe {
+            imxrt_rt::load_deferred(imxrt_rt::Section::Custom("*libboard*:*(.rodata .rodata.*)"));
+            __layout_filler()
+        };
+    }
+}
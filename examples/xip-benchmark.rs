@@ -0,0 +1,144 @@
+//! Measure how long it takes to execute code from flash.
+//!
+//! The example counts the CPU cycles needed to run a workload that
+//! executes in place from flash. It stores the cycle count of the
+//! first, cold run in `COLD_CYCLES`, and the cycle count of the
+//! following, warm run in `WARM_CYCLES`. Read these statics with a
+//! debugger. Then, the example blinks the LED.
+//!
+//! Use this example to compare FlexSPI AHB buffer and prefetch
+//! configurations. Change (or remove) the `flexspi_ahb` call for your
+//! board in `board/build.rs`, rebuild the example, and compare the
+//! counts. Make sure that the board places `.text` in flash; otherwise,
+//! this example measures TCM or OCRAM performance. The counts depend on
+//! the flash part, its clock, and the core clock, so take measurements
+//! on your hardware.
+//!
+//! The runtime doesn't enable the instruction cache. If something else
+//! enables it, the warm run may execute from the cache, and only the cold
+//! run reflects the AHB configuration.
+//!
+//! This repository doesn't include measurements yet. When you share
+//! results, report `COLD_CYCLES` and `WARM_CYCLES` with and without
+//! `flexspi_ahb`, and name the board, the flash part and its clock, and
+//! the core clock.
+
+#![no_std]
+#![no_main]
+
+use core::sync::atomic::{AtomicU32, Ordering};
+
+const PIT_PERIOD_US: u32 = 1_000_000;
+
+/// Cycles taken by the first run of the workload.
+static COLD_CYCLES: AtomicU32 = AtomicU32::new(0);
+/// Cycles taken by the second run of the workload.
+static WARM_CYCLES: AtomicU32 = AtomicU32::new(0);
+
+const DEMCR: *mut u32 = 0xE000_EDFC as *mut u32;
+const DWT_CTRL: *mut u32 = 0xE000_1000 as *mut u32;
+const DWT_CYCCNT: *const u32 = 0xE000_1004 as *const u32;
+
+/// Enable the DWT cycle counter.
+fn enable_cycle_counter() {
+    // Safety: DEMCR and DWT_CTRL are valid on all Cortex-M7 cores.
+    // Setting these bits only enables the cycle counter.
+    unsafe {
+        DEMCR.write_volatile(DEMCR.read_volatile() | 1 << 24);
+        DWT_CTRL.write_volatile(DWT_CTRL.read_volatile() | 1);
+    }
+}
+
+fn cycles() -> u32 {
+    // Safety: the cycle counter is always valid to read.
+    unsafe { DWT_CYCCNT.read_volatile() }
+}
+
+macro_rules! step {
+    ($state:ident, $multiplier:literal, $($shift:literal),*) => {
+        $(
+            $state ^= $state << $shift;
+            $state = $state.rotate_right($shift + 1).wrapping_mul($multiplier);
+            if $state & 1 == 0 {
+                $state = $state.wrapping_add($shift);
+            }
+        )*
+    };
+}
+
+macro_rules! stages {
+    ($($stage:ident = $multiplier:literal),*) => {
+        $(
+            /// One part of the workload. Each stage has its own code.
+            #[inline(never)]
+            fn $stage(mut state: u32) -> u32 {
+                step!(
+                    state, $multiplier, 1, 2, 3, 4, 5, 6, 7, 8, 9, 10, 11, 12, 13, 14, 15, 16,
+                    17, 18, 19, 20, 21, 22, 23, 24
+                );
+                state
+            }
+        )*
+
+        /// A branchy workload that executes from flash.
+        ///
+        /// Together, the stages are larger than the AHB buffers, so the
+        /// workload exercises buffer misses and prefetching.
+        #[inline(never)]
+        fn workload(mut state: u32) -> u32 {
+            for _ in 0..16 {
+                $(state = $stage(state);)*
+            }
+            state
+        }
+    };
+}
+
+stages!(
+    stage0 = 0x9E37_79B9,
+    stage1 = 0x85EB_CA6B,
+    stage2 = 0xC2B2_AE35,
+    stage3 = 0x27D4_EB2F,
+    stage4 = 0x1656_67B1,
+    stage5 = 0xD3A2_646C,
+    stage6 = 0xFD70_46C5,
+    stage7 = 0xB55A_4F09
+);
+
+#[imxrt_rt::entry]
+fn main() -> ! {
+    let board::Resources { mut pit, led, .. } = board::prepare(PIT_PERIOD_US).unwrap();
+    enable_cycle_counter();
+
+    let mut seed = 0x1234_5678;
+    for counter in [&COLD_CYCLES, &WARM_CYCLES] {
+        let start = cycles();
+        seed = workload(core::hint::black_box(seed));
+        counter.store(cycles().wrapping_sub(start), Ordering::Relaxed);
+    }
+    core::hint::black_box(seed);
+
+    loop {
+        led.toggle();
+        pit.blocking_delay();
+    }
+}
+
+use imxrt_rt::exception;
+
+#[exception]
+unsafe fn DefaultHandler(_irqn: i16) {
+    uh_oh()
+}
+
+#[exception]
+unsafe fn HardFault(_: &imxrt_rt::ExceptionFrame) -> ! {
+    uh_oh()
+}
+
+#[inline(never)]
+fn uh_oh() -> ! {
+    loop {
+        core::sync::atomic::fence(core::sync::atomic::Ordering::SeqCst)
+    }
+}
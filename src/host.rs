@@ -8,6 +8,15 @@
 // to consider.
 #![warn(clippy::wildcard_enum_match_arm)]
 
+mod compress;
+mod image;
+mod integrity;
+
+use compress::COMPRESSION_MAGIC;
+pub use compress::compress_image;
+pub use integrity::checksum_image;
+use integrity::{CRC32_TABLE, INTEGRITY_MAGIC};
+
 use std::{
     env,
     fmt::Display,
@@ -42,6 +51,149 @@ pub enum Memory {
     Ocram,
 }
 
+impl Memory {
+    /// Memory identifier.
+    ///
+    /// These values are stored in the image. Keep them in sync with the
+    /// target runtime.
+    const fn id(self) -> u32 {
+        match self {
+            Memory::Flash => 0,
+            Memory::Itcm => 1,
+            Memory::Dtcm => 2,
+            Memory::Ocram => 3,
+        }
+    }
+}
+
+/// Placement of a section that may spill into a second memory.
+///
+/// Use with [`RuntimeBuilder::text`]. A [`Memory`] converts into a
+/// `Placement` that places the entire section in that memory.
+///
+/// ```
+/// use imxrt_rt::{Memory, Placement};
+///
+/// assert_eq!(Placement::from(Memory::Itcm), Placement::Prefer(Memory::Itcm));
+/// assert_eq!(
+///     Placement::Prefer(Memory::Itcm).fallback(Memory::Flash),
+///     Placement::Spill {
+///         preferred: Memory::Itcm,
+///         fallback: Memory::Flash,
+///     }
+/// );
+/// ```
+#[non_exhaustive]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Placement {
+    /// Place the entire section in this memory.
+    ///
+    /// If the section doesn't fit, linking fails. Use [`fallback`](Self::fallback)
+    /// to spill the remainder into another memory.
+    Prefer(Memory),
+    /// Place as much of the section as possible in the `preferred` memory,
+    /// then place the remainder in the `fallback` memory.
+    ///
+    /// The linker decides what spills by input section. Sections named
+    /// `.text.hot` or `.text.hot.*` are placed first, so they're the
+    /// last to spill. If the fallback memory is flash, the remainder
+    /// executes in place. Otherwise, the runtime copies the remainder
+    /// from flash, just like it copies the preferred portion.
+    ///
+    /// This requires the linker's `--enable-non-contiguous-regions` option.
+    /// [`build()`](RuntimeBuilder::build) asks Cargo to pass that option
+    /// when linking the package that calls `build()`. If you link a
+    /// different package, you need to pass `-C link-arg=--enable-non-contiguous-regions`
+    /// yourself. Without the option, the linker never spills, and
+    /// linking fails if the section doesn't fit.
+    ///
+    /// The linker also won't spill if code in the preferred memory calls
+    /// code that's out of branch range, like a function in the `.xip`
+    /// section. The linker places a range extension thunk for that call at
+    /// the end of the section, and it doesn't spill past the thunk.
+    Spill {
+        /// The memory that takes as much of the section as possible.
+        preferred: Memory,
+        /// The memory that takes the remainder.
+        fallback: Memory,
+    },
+}
+
+impl Placement {
+    /// Spill the section into `fallback` when it doesn't fit in the preferred memory.
+    ///
+    /// If this placement already has a fallback, `fallback` replaces it.
+    pub const fn fallback(self, fallback: Memory) -> Self {
+        Placement::Spill {
+            preferred: self.preferred(),
+            fallback,
+        }
+    }
+
+    /// Returns the memory that's filled first.
+    const fn preferred(self) -> Memory {
+        match self {
+            Placement::Prefer(preferred) | Placement::Spill { preferred, .. } => preferred,
+        }
+    }
+
+    /// Returns the memory that takes the remainder, if any.
+    const fn spill(self) -> Option<Memory> {
+        match self {
+            Placement::Prefer(_) => None,
+            Placement::Spill { fallback, .. } => Some(fallback),
+        }
+    }
+}
+
+impl From<Memory> for Placement {
+    fn from(memory: Memory) -> Self {
+        Placement::Prefer(memory)
+    }
+}
+
+/// A section in the final program.
+///
+/// Use with [`RuntimeBuilder::align`] to control section alignment, and with
+/// [`RuntimeBuilder::defer`] to defer a section's copy.
+#[non_exhaustive]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Section {
+    /// Code.
+    Text,
+    /// Read-only data.
+    Rodata,
+    /// Mutable data.
+    Data,
+    /// Zero-initialized data.
+    Bss,
+    /// Uninitialized data.
+    Uninit,
+    /// Stack memory.
+    Stack,
+    /// The heap.
+    Heap,
+    /// A section you defined with the builder.
+    ///
+    /// This is either the name of a [fixed section](RuntimeBuilder::fixed_section),
+    /// or the pattern of an [input placement](RuntimeBuilder::place_input).
+    Custom(String),
+}
+
+/// The runtime copies, and zeroes, sections in bursts of this many bytes.
+const BURST_ALIGN: usize = 32;
+
+impl Section {
+    /// The alignment used when the user doesn't specify an alignment.
+    const fn default_alignment(&self) -> usize {
+        match self {
+            Section::Text | Section::Rodata | Section::Data | Section::Bss => BURST_ALIGN,
+            Section::Stack => 8,
+            Section::Uninit | Section::Heap | Section::Custom(_) => 4,
+        }
+    }
+}
+
 /// The FlexSPI peripheral that interfaces your flash chip.
 ///
 /// The [`RuntimeBuilder`] selects `FlexSpi1` for nearly all chip
@@ -106,6 +258,113 @@ impl FlexSpi {
     fn supported_for_family(self, family: Family) -> bool {
         self.start_address(family).is_some()
     }
+    /// The address of the FlexSPI controller's registers.
+    ///
+    /// Returns `None` if the runtime can't configure the controller.
+    fn register_address(self, family: Family) -> Option<u32> {
+        match (self, family) {
+            (FlexSpi::FlexSpi1, Family::Imxrt1010) => Some(0x400A_0000),
+            (
+                FlexSpi::FlexSpi1,
+                Family::Imxrt1015
+                | Family::Imxrt1020
+                | Family::Imxrt1040
+                | Family::Imxrt1050
+                | Family::Imxrt1060
+                | Family::Imxrt1064,
+            ) => Some(0x402A_8000),
+            (FlexSpi::FlexSpi2, Family::Imxrt1040 | Family::Imxrt1060 | Family::Imxrt1064) => {
+                Some(0x402A_4000)
+            }
+            (FlexSpi::FlexSpi1, Family::Imxrt1160 | Family::Imxrt1170) => Some(0x400C_C000),
+            (FlexSpi::FlexSpi2, Family::Imxrt1160 | Family::Imxrt1170) => Some(0x400D_0000),
+            (
+                FlexSpi::FlexSpi2,
+                Family::Imxrt1010 | Family::Imxrt1015 | Family::Imxrt1020 | Family::Imxrt1050,
+            )
+            | (FlexSpi::FlexSpi1 | FlexSpi::FlexSpi2, Family::Imxrt1180) => None,
+        }
+    }
+}
+
+/// An AHB RX buffer in the FlexSPI controller.
+///
+/// Use with [`FlexSpiAhb`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct AhbRxBuffer {
+    /// The ID of the AHB master that reads through this buffer.
+    ///
+    /// See your chip's reference manual for the master IDs.
+    pub master_id: u8,
+    /// The size of the buffer, in bytes. This must be a multiple of 8.
+    pub size: usize,
+    /// Prefetch into this buffer.
+    ///
+    /// Prefetching requires [`FlexSpiAhb::prefetch`].
+    pub prefetch: bool,
+}
+
+/// How the FlexSPI controller buffers AHB reads from flash.
+///
+/// Use with [`RuntimeBuilder::flexspi_ahb`].
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct FlexSpiAhb {
+    /// Enable AHB read prefetching.
+    pub prefetch: bool,
+    /// The AHB RX buffers, starting with buffer 0.
+    ///
+    /// The runtime disables the buffers that aren't in this collection, setting
+    /// their size to zero.
+    pub buffers: Vec<AhbRxBuffer>,
+}
+
+impl FlexSpiAhb {
+    /// Returns the number of AHB RX buffers, and the maximum size of a buffer.
+    ///
+    /// The maximum size also bounds the sum of all buffer sizes on the 10xx chips.
+    fn limits(family: Family) -> (usize, usize) {
+        match family {
+            Family::Imxrt1010
+            | Family::Imxrt1015
+            | Family::Imxrt1020
+            | Family::Imxrt1040
+            | Family::Imxrt1050
+            | Family::Imxrt1060
+            | Family::Imxrt1064 => (4, 1024),
+            Family::Imxrt1160 | Family::Imxrt1170 | Family::Imxrt1180 => (8, 0x3FF * 8),
+        }
+    }
+
+    fn check(&self, family: Family) -> Result<(), String> {
+        let (count, max_size) = Self::limits(family);
+        if self.buffers.len() > count {
+            return Err(format!(
+                "Chip {family:?} has {count} AHB RX buffers, but the configuration has {}",
+                self.buffers.len()
+            ));
+        }
+        for buffer in &self.buffers {
+            if buffer.size % 8 != 0 || buffer.size > max_size || buffer.master_id > 15 {
+                return Err(format!("Invalid AHB RX buffer {buffer:?}"));
+            }
+        }
+        let total: usize = self.buffers.iter().map(|buffer| buffer.size).sum();
+        if count == 4 && total > max_size {
+            return Err(format!(
+                "Chip {family:?} has {max_size} bytes of AHB RX buffers, but the configuration uses {total}"
+            ));
+        }
+        Ok(())
+    }
+
+    /// Returns the AHBRXBUFxCR0 register values.
+    fn buffer_config(&self) -> impl Iterator<Item = u32> + '_ {
+        self.buffers.iter().map(|buffer| {
+            u32::from(buffer.prefetch) << 31
+                | u32::from(buffer.master_id) << 16
+                | (buffer.size / 8) as u32
+        })
+    }
 }
 
 impl Display for Memory {
@@ -119,6 +378,48 @@ impl Display for Memory {
     }
 }
 
+/// Size of the boot timings record, in bytes. Keep this in sync with the
+/// target runtime.
+const BOOT_TIMINGS_SIZE: usize = 13 * 4;
+
+/// Size of the crash record, including its header, in bytes. Keep this in sync
+/// with the target runtime.
+const CRASH_RECORD_SIZE: usize = 49 * 4;
+
+/// Size of the crash record handler's stack, in bytes. Large enough for an
+/// unoptimized handler.
+const CRASH_STACK_SIZE: usize = 2 * 1024;
+
+/// Replaced with the entries of the copy table in the primary linker script.
+const COPY_TABLE_MARKER: &str = "/* imxrt-rt: generated copy table */";
+/// Replaced with the entries of the deferred table in the primary linker script.
+const DEFERRED_TABLE_MARKER: &str = "/* imxrt-rt: generated deferred table */";
+/// Replaced with the compression table in the primary linker script.
+const COMPRESSION_TABLE_MARKER: &str = "/* imxrt-rt: generated compression table */";
+/// Replaced with the integrity table in the primary linker script.
+const INTEGRITY_TABLE_MARKER: &str = "/* imxrt-rt: generated integrity table */";
+/// Replaced with the CRC lookup table in the primary linker script.
+const CRC_TABLE_MARKER: &str = "/* imxrt-rt: generated CRC table */";
+/// Replaced with generated output sections in the primary linker script.
+const OUTPUT_SECTIONS_MARKER: &str = "/* imxrt-rt: generated output sections */";
+
+/// Identifies a deferred section in the deferred table.
+///
+/// This is the 32-bit FNV-1a hash of the section's name. Keep this in sync
+/// with the target runtime.
+fn deferred_key(name: &str) -> u32 {
+    name.bytes().fold(0x811C_9DC5, |hash, byte| {
+        (hash ^ u32::from(byte)).wrapping_mul(0x0100_0193)
+    })
+}
+
+/// The alignment of a vector table with `interrupts` device interrupts.
+///
+/// VTOR requires the table to be aligned to its size, rounded up to a power of two.
+fn vector_table_align(interrupts: usize) -> usize {
+    ((16 + interrupts) * 4).next_power_of_two().max(1024)
+}
+
 /// Define an alias for `name` that maps to a memory block named `placement`.
 fn region_alias(output: &mut dyn Write, name: &str, placement: Memory) -> io::Result<()> {
     writeln!(output, "REGION_ALIAS(\"REGION_{name}\", {placement});")
@@ -129,6 +430,11 @@ struct FlashOpts {
     size: usize,
     offset: u32,
     flexspi: FlexSpi,
+    /// If set, the load image is compressed after linking. This is the
+    /// flash size used when linking the uncompressed image.
+    uncompressed_size: Option<usize>,
+    /// If set, the runtime configures the FlexSPI AHB buffers.
+    ahb: Option<FlexSpiAhb>,
 }
 
 impl FlashOpts {
@@ -145,6 +451,11 @@ impl FlashOpts {
     fn is_boot_image(&self) -> bool {
         self.offset == 0
     }
+
+    /// The flash size used when linking.
+    fn link_size(&self) -> usize {
+        self.uncompressed_size.unwrap_or(self.size).max(self.size)
+    }
 }
 
 #[derive(Debug, Clone, PartialEq, Eq)]
@@ -221,10 +532,12 @@ impl EnvOverride {
 /// addresses. This means the heap will grow up into reserved memory above DTCM and OCRAM
 /// for most chip families.
 ///
-/// The vector table requires a 1024-byte alignment. The vector table's placement is prioritized
-/// above all other sections, except the stack. If placing the stack and vector table in the
-/// same section (which is the default behavior), consider keeping the stack size as a multiple
-/// of 1 KiB to minimize internal fragmentation.
+/// The vector table is aligned to its size, rounded up to a power of two. That's 1024 bytes
+/// for up to 240 [interrupts](RuntimeBuilder::interrupts), and 2048 bytes for more. The vector
+/// table's placement is prioritized above all other sections, except the stack. If placing the
+/// stack and vector table in the same section (which is the default behavior), consider keeping
+/// the stack size as a multiple of the vector table's alignment to minimize internal
+/// fragmentation.
 ///
 /// # Default values
 ///
@@ -244,8 +557,11 @@ impl EnvOverride {
 /// b.rodata(Memory::Ocram); // Copied from flash.
 /// b.data(Memory::Ocram);   // Copied from flash.
 /// b.vectors(Memory::Dtcm); // Copied from flash.
+/// b.interrupts(0);         // Only the device's interrupts.
 /// b.bss(Memory::Ocram);
 /// b.uninit(Memory::Ocram);
+/// b.retained(Memory::Dtcm);
+/// b.retained_size(0);      // Only as large as its contents.
 /// b.stack(Memory::Dtcm);
 /// b.stack_size(8 * 1024);  // 8 KiB stack.
 /// b.heap(Memory::Dtcm);    // Heap in DTCM...
@@ -336,21 +652,182 @@ impl EnvOverride {
 pub struct RuntimeBuilder {
     family: Family,
     flexram_layout: Vec<FlexRamKind>,
-    text: Memory,
+    flexram_fuses: Option<u32>,
+    restrict_tcm_sizes: bool,
+    core_clock_mhz: Option<u32>,
+    boot_timings: bool,
+    crash_record: bool,
+    reset_reason: bool,
+    integrity_check: bool,
+    init_arrays: bool,
+    unwind_tables: Option<Memory>,
+    text: Placement,
     rodata: Memory,
     data: Memory,
     vectors: Memory,
+    interrupts: usize,
+    relocated_vectors: Option<Memory>,
     bss: Memory,
     uninit: Memory,
+    retained: Memory,
+    retained_size: usize,
     stack: Memory,
     stack_size: EnvOverride,
     heap: Memory,
     heap_size: EnvOverride,
     flash_opts: Option<FlashOpts>,
+    input_placements: Vec<InputPlacement>,
+    fixed_sections: Vec<FixedSection>,
+    alignments: Vec<(Section, usize)>,
+    watchdogs: Vec<(Watchdog, WatchdogMode)>,
+    deferred: Vec<Section>,
     linker_script_name: String,
     device_script_name: String,
 }
 
+/// Place input sections matching `pattern` into `memory`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct InputPlacement {
+    pattern: String,
+    memory: Memory,
+}
+
+/// A section at a fixed address.
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct FixedSection {
+    name: String,
+    address: u32,
+    size: usize,
+    kind: FixedSectionKind,
+}
+
+/// Describes the contents of a fixed section.
+///
+/// Use with [`RuntimeBuilder::fixed_section`].
+#[non_exhaustive]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FixedSectionKind {
+    /// The runtime doesn't initialize the section.
+    ///
+    /// This is like the `.uninit` section. The contents survive a
+    /// reset, and another program may have written them.
+    NoLoad,
+    /// The runtime initializes the section from the program image.
+    ///
+    /// This is like the `.data` section.
+    Initialized,
+}
+
+/// A watchdog that the runtime configures when it starts.
+///
+/// Use with [`RuntimeBuilder::watchdog`].
+#[non_exhaustive]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Watchdog {
+    /// WDOG1. On the 1180, this is the CM33's RTWDOG1.
+    Wdog1,
+    /// WDOG2. On the 1180, this is the CM33's RTWDOG2.
+    Wdog2,
+    /// RTWDOG. On the 1160 and 1170, this is RTWDOG3. The 1180 doesn't have
+    /// this watchdog.
+    Rtwdog,
+}
+
+/// What the runtime does with a watchdog when it starts.
+///
+/// Use with [`RuntimeBuilder::watchdog`].
+#[non_exhaustive]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum WatchdogMode {
+    /// Disable the watchdog.
+    ///
+    /// WDOG1 and WDOG2 can't be disabled once they're enabled. If the boot ROM
+    /// enabled one of these watchdogs, it keeps running.
+    Disable,
+    /// Leave the watchdog as the boot ROM configured it.
+    Keep,
+    /// Enable the watchdog with the given timeout, in milliseconds.
+    ///
+    /// WDOG1 and WDOG2 support timeouts between 500ms and 128s, rounded up to
+    /// the next 500ms. The RTWDOG counts the 32.768KHz low-power oscillator, and
+    /// it supports timeouts up to 511s. On the 1180, WDOG1 and WDOG2 are RTWDOGs,
+    /// and they support the RTWDOG's timeouts.
+    ///
+    /// When it disables or enables WDOG1 or WDOG2, the runtime also disables that
+    /// watchdog's power-down counter.
+    Enable {
+        /// Time until the watchdog resets the chip, in milliseconds.
+        timeout_ms: u32,
+    },
+}
+
+impl WatchdogMode {
+    /// The configuration for WDOG1 and WDOG2.
+    ///
+    /// The target runtime leaves the watchdog alone if this is zero, and it
+    /// disables the watchdog if this is one. Otherwise, this is the value for
+    /// WDOG[WCR].
+    fn wdog_config(self) -> Result<u32, String> {
+        match self {
+            WatchdogMode::Keep => Ok(0),
+            WatchdogMode::Disable => Ok(1),
+            WatchdogMode::Enable { timeout_ms } if (500..=128_000).contains(&timeout_ms) => {
+                // WT counts half seconds, plus one. Set WDE, and don't assert
+                // WDOG_B or a software reset.
+                let wt = timeout_ms.div_ceil(500) - 1;
+                Ok(wt << 8 | 1 << 5 | 1 << 4 | 1 << 2)
+            }
+            WatchdogMode::Enable { timeout_ms } => Err(format!(
+                "WDOG timeout must be between 500ms and 128000ms, not {timeout_ms}ms"
+            )),
+        }
+    }
+
+    /// The configuration for the RTWDOG.
+    ///
+    /// The first value is like [`wdog_config`](Self::wdog_config); when enabled,
+    /// it's the value for RTWDOG[CS]. The second value is for RTWDOG[TOVAL].
+    fn rtwdog_config(self) -> Result<(u32, u32), String> {
+        // Enable the watchdog, and keep updates allowed. Count the LPO clock,
+        // and accept 32-bit unlock commands.
+        const CS: u32 = 1 << 13 | 1 << 8 | 1 << 7 | 1 << 5;
+        const PRES: u32 = 1 << 12;
+        match self {
+            WatchdogMode::Keep => Ok((0, 0)),
+            WatchdogMode::Disable => Ok((1, 0)),
+            WatchdogMode::Enable { timeout_ms } => {
+                let ticks = |hz: u64| (u64::from(timeout_ms) * hz).div_ceil(1000);
+                match (ticks(32_768), ticks(32_768 / 256)) {
+                    (0, _) => Err("RTWDOG timeout must be at least 1ms".into()),
+                    (ticks, _) if ticks <= 0xFFFF => Ok((CS, ticks as u32)),
+                    (_, ticks) if ticks <= 0xFFFF => Ok((CS | PRES, ticks as u32)),
+                    _ => Err(format!(
+                        "RTWDOG timeout must be less than 511s, not {timeout_ms}ms"
+                    )),
+                }
+            }
+        }
+    }
+}
+
+/// Register values that run the core at a specific frequency.
+///
+/// The default values keep the boot ROM's clock configuration.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+struct CoreClock {
+    /// CCM_ANALOG_PLL_ARM[DIV_SELECT]. On the 1160 and 1170,
+    /// ANADIG_PLL_ARM_PLL_CTRL[DIV_SELECT].
+    div_select: u32,
+    /// CCM_CACRR[ARM_PODF]. On the 1160 and 1170,
+    /// ANADIG_PLL_ARM_PLL_CTRL[POST_DIV_SEL].
+    arm_podf: u32,
+    /// CCM_CBCDR[IPG_PODF]. Zero on the 1160 and 1170.
+    ipg_podf: u32,
+    /// DCDC_REG3[TRG], the minimum core voltage. On the 1160 and 1170,
+    /// DCDC_CTRL1[VDD1P0CTRL_TRG].
+    dcdc_trg: u32,
+}
+
 const DEFAULT_LINKER_SCRIPT_NAME: &str = "imxrt-link.x";
 const DEFAULT_DEVICE_SCRIPT_NAME: &str = "device.x";
 
@@ -363,12 +840,25 @@ impl RuntimeBuilder {
         Self {
             family,
             flexram_layout: family.default_flexram_layout(),
-            text: Memory::Itcm,
+            flexram_fuses: None,
+            restrict_tcm_sizes: false,
+            core_clock_mhz: None,
+            boot_timings: false,
+            crash_record: false,
+            reset_reason: false,
+            integrity_check: false,
+            init_arrays: false,
+            unwind_tables: None,
+            text: Placement::Prefer(Memory::Itcm),
             rodata: Memory::Ocram,
             data: Memory::Ocram,
             vectors: Memory::Dtcm,
+            interrupts: 0,
+            relocated_vectors: None,
             bss: Memory::Ocram,
             uninit: Memory::Ocram,
+            retained: Memory::Dtcm,
+            retained_size: 0,
             stack: Memory::Dtcm,
             stack_size: EnvOverride::new(8 * 1024),
             heap: Memory::Dtcm,
@@ -377,7 +867,14 @@ impl RuntimeBuilder {
                 size: flash_size,
                 offset: 0,
                 flexspi: FlexSpi::family_default(family),
+                uncompressed_size: None,
+                ahb: None,
             }),
+            input_placements: Vec::new(),
+            fixed_sections: Vec::new(),
+            alignments: Vec::new(),
+            watchdogs: Vec::new(),
+            deferred: Vec::new(),
             linker_script_name: DEFAULT_LINKER_SCRIPT_NAME.into(),
             device_script_name: DEFAULT_DEVICE_SCRIPT_NAME.into(),
         }
@@ -400,12 +897,25 @@ impl RuntimeBuilder {
         Self {
             family,
             flexram_layout: family.default_flexram_layout(),
-            text: Memory::Itcm,
+            flexram_fuses: None,
+            restrict_tcm_sizes: false,
+            core_clock_mhz: None,
+            boot_timings: false,
+            crash_record: false,
+            reset_reason: false,
+            integrity_check: false,
+            init_arrays: false,
+            unwind_tables: None,
+            text: Placement::Prefer(Memory::Itcm),
             rodata: Memory::Ocram,
             data: Memory::Ocram,
             vectors: Memory::Dtcm,
+            interrupts: 0,
+            relocated_vectors: None,
             bss: Memory::Ocram,
             uninit: Memory::Ocram,
+            retained: Memory::Dtcm,
+            retained_size: 0,
             stack: Memory::Dtcm,
             stack_size: EnvOverride::new(8 * 1024),
             heap: Memory::Dtcm,
@@ -414,7 +924,14 @@ impl RuntimeBuilder {
                 size: partition_size,
                 offset: partition_offset,
                 flexspi: FlexSpi::family_default(family),
+                uncompressed_size: None,
+                ahb: None,
             }),
+            input_placements: Vec::new(),
+            fixed_sections: Vec::new(),
+            alignments: Vec::new(),
+            watchdogs: Vec::new(),
+            deferred: Vec::new(),
             linker_script_name: DEFAULT_LINKER_SCRIPT_NAME.into(),
             device_script_name: DEFAULT_DEVICE_SCRIPT_NAME.into(),
         }
@@ -425,17 +942,35 @@ impl RuntimeBuilder {
         Self {
             family,
             flexram_layout: family.default_flexram_layout(),
-            text: Memory::Itcm,
+            flexram_fuses: None,
+            restrict_tcm_sizes: false,
+            core_clock_mhz: None,
+            boot_timings: false,
+            crash_record: false,
+            reset_reason: false,
+            integrity_check: false,
+            init_arrays: false,
+            unwind_tables: None,
+            text: Placement::Prefer(Memory::Itcm),
             rodata: Memory::Ocram,
             data: Memory::Ocram,
             vectors: Memory::Dtcm,
+            interrupts: 0,
+            relocated_vectors: None,
             bss: Memory::Ocram,
             uninit: Memory::Ocram,
+            retained: Memory::Dtcm,
+            retained_size: 0,
             stack: Memory::Dtcm,
             stack_size: EnvOverride::new(8 * 1024),
             heap: Memory::Dtcm,
             heap_size: EnvOverride::new(0),
             flash_opts: None,
+            input_placements: Vec::new(),
+            fixed_sections: Vec::new(),
+            alignments: Vec::new(),
+            watchdogs: Vec::new(),
+            deferred: Vec::new(),
             linker_script_name: DEFAULT_LINKER_SCRIPT_NAME.into(),
             device_script_name: DEFAULT_DEVICE_SCRIPT_NAME.into(),
         }
@@ -457,12 +992,254 @@ impl RuntimeBuilder {
     /// controller.
     pub fn flexram_layout(&mut self, flexram_layout: &[FlexRamKind]) -> &mut Self {
         self.flexram_layout = Vec::from(flexram_layout);
+        self.flexram_fuses = None;
+        self
+    }
+
+    /// Use the FlexRAM configuration defined by your chip's fuses.
+    ///
+    /// `fuse_value` is the value of the FlexRAM configuration fuse field.
+    /// The field is typically named `FLEXRAM_CFG` or `DEFAULT_FLEXRAM_PART`;
+    /// consult your chip's fusemap. The builder decodes the fuse value into
+    /// ITCM, DTCM, and OCRAM sizes for the memory map. See
+    /// [`Family::flexram_banks_from_fuses`] for the decoding.
+    ///
+    /// Unlike [`flexram_banks`](Self::flexram_banks) and [`flexram_layout`](Self::flexram_layout),
+    /// the runtime does not configure FlexRAM. It leaves the FlexRAM configuration
+    /// registers untouched, so the processor keeps the configuration selected by
+    /// the fuses, or by the bootloader that launched your program. Make sure that
+    /// the fuse value you provide here matches that configuration.
+    ///
+    /// A later call to `flexram_banks` or `flexram_layout` overrides this setting.
+    /// If the fuse value cannot be decoded, [`build()`](Self::build) returns an error.
+    /// The builder can't decode the 1180's fuses.
+    pub fn flexram_from_fuses(&mut self, fuse_value: u32) -> &mut Self {
+        self.flexram_fuses = Some(fuse_value);
+        self
+    }
+
+    /// Restrict the TCM sizes to match the FlexRAM layout.
+    ///
+    /// By default, the runtime keeps the processor's maximum TCM sizes. An access
+    /// beyond the allocated ITCM or DTCM banks doesn't fault; it simply hits nothing.
+    /// When `restrict` is `true`, the runtime programs the CM7_CFGITCMSZ and
+    /// CM7_CFGDTCMSZ fields of IOMUXC_GPR14 so that these accesses bus fault.
+    ///
+    /// The processor only supports power-of-two TCM sizes. If the number of ITCM
+    /// or DTCM banks doesn't produce a power-of-two size, the runtime rounds the
+    /// size up. Accesses into the rounded-up portion of the TCM won't fault.
+    ///
+    /// This is only available for the 10xx chips. If you enable this for any other
+    /// chip, [`build()`](Self::build) returns an error.
+    pub fn restrict_tcm_sizes(&mut self, restrict: bool) -> &mut Self {
+        self.restrict_tcm_sizes = restrict;
+        self
+    }
+
+    /// Run the core at `mhz` before the runtime copies any sections.
+    ///
+    /// By default, the runtime keeps the boot ROM's clock configuration, and your
+    /// firmware changes the clocks in `main()`. When you select a frequency, the
+    /// runtime raises the core voltage, if necessary, and runs the core from the
+    /// ARM PLL at that frequency. The IPG clock runs as fast as possible, up to 150MHz.
+    /// Section copies, your pre-init hook, and everything after run at the new
+    /// frequency.
+    ///
+    /// ```no_run
+    /// use imxrt_rt::{Family, RuntimeBuilder};
+    ///
+    /// RuntimeBuilder::from_flexspi(Family::Imxrt1060, 16 * 1024 * 1024)
+    ///     .core_clock_mhz(600)
+    ///     .build()
+    ///     .unwrap();
+    /// ```
+    ///
+    /// The ARM PLL produces frequencies between 81MHz and 600MHz, but not every
+    /// frequency in that range. Frequencies above 528MHz raise the core voltage to
+    /// 1.25V; otherwise, the runtime ensures at least 1.15V. The runtime never
+    /// lowers the core voltage.
+    ///
+    /// On the 1160 and 1170, the ARM PLL produces frequencies between 156MHz and
+    /// 996MHz, but the 1160 runs up to 600MHz. Frequencies above
+    /// 600MHz raise the DCDC's VDD1P0 output to the 1.125V overdrive voltage;
+    /// otherwise, the runtime ensures at least 1.0V. The runtime assumes that the
+    /// DCDC supplies the core, and it doesn't change the bus or peripheral clocks.
+    ///
+    /// This is only available for the 1040, 1050, 1060, 1064, 1160, and 1170. If
+    /// you select a frequency for any other chip, or if the chip can't produce
+    /// the frequency, [`build()`](Self::build) returns an error.
+    pub fn core_clock_mhz(&mut self, mhz: u32) -> &mut Self {
+        self.core_clock_mhz = Some(mhz);
+        self
+    }
+
+    /// Choose what the runtime does with a watchdog when it starts.
+    ///
+    /// By default, the runtime disables the RTWDOG, and it leaves WDOG1 and WDOG2
+    /// as the boot ROM configured them. On the 1180, WDOG1 and WDOG2 are the CM33's
+    /// RTWDOGs, and the runtime disables them by default. To keep a watchdog running from reset,
+    /// [enable](WatchdogMode::Enable) it, or [keep](WatchdogMode::Keep) the boot
+    /// ROM's configuration. An enabled watchdog resets the chip unless your firmware
+    /// services it before the timeout.
+    ///
+    /// ```no_run
+    /// use imxrt_rt::{Family, RuntimeBuilder, Watchdog, WatchdogMode};
+    ///
+    /// RuntimeBuilder::from_flexspi(Family::Imxrt1060, 16 * 1024 * 1024)
+    ///     .watchdog(Watchdog::Wdog1, WatchdogMode::Enable { timeout_ms: 2000 })
+    ///     .watchdog(Watchdog::Rtwdog, WatchdogMode::Keep)
+    ///     .build()
+    ///     .unwrap();
+    /// ```
+    ///
+    /// The 1180 doesn't have a separate RTWDOG. If you select anything other than
+    /// [`Keep`](WatchdogMode::Keep) for the 1180's RTWDOG, or if you select an
+    /// unsupported timeout, [`build()`](Self::build) returns an error.
+    pub fn watchdog(&mut self, watchdog: Watchdog, mode: WatchdogMode) -> &mut Self {
+        self.watchdogs.retain(|(wdog, _)| *wdog != watchdog);
+        self.watchdogs.push((watchdog, mode));
+        self
+    }
+
+    /// Measure the duration of each boot phase.
+    ///
+    /// When `enable` is `true`, the runtime enables the DWT cycle counter when it
+    /// starts. It records the cycle count after each phase of its startup sequence.
+    /// The record is in the `.uninit` section. In your firmware, use
+    /// `imxrt_rt::boot_timings` to read the record.
+    ///
+    /// To measure the initialization of `.data` and `.bss`, the runtime initializes
+    /// them itself, like it does for [static constructors](Self::init_arrays).
+    ///
+    /// By default, the runtime doesn't measure its boot phases, and it doesn't
+    /// touch the DWT.
+    pub fn boot_timings(&mut self, enable: bool) -> &mut Self {
+        self.boot_timings = enable;
+        self
+    }
+
+    /// Save a crash record when the program takes a `HardFault`.
+    ///
+    /// When `enable` is `true`, the builder reserves a crash record at the start of
+    /// the `.retained` section, and the runtime provides a `HardFault` handler. The
+    /// handler saves the exception frame, the fault status and address registers,
+    /// and a snippet of the main stack into the crash record. Then, it resets the
+    /// processor. On the next boot, use `imxrt_rt::crash_record` to read the record,
+    /// and `imxrt_rt::clear_crash_record` to clear it.
+    ///
+    /// The handler runs on its own 2KiB stack, reserved in the `.uninit` section, so
+    /// it can save a record after the main stack overflows.
+    ///
+    /// If your firmware defines its own `HardFault` handler, the runtime doesn't
+    /// install its handler. The crash record is still reserved, but nothing writes
+    /// it. The crash record survives the same resets as the rest of the `.retained`
+    /// section; see [`retained`](Self::retained). It doesn't count towards the
+    /// [`retained_size`](Self::retained_size) reservation.
+    ///
+    /// By default, the runtime uses the `HardFault` handler from `cortex-m-rt`.
+    pub fn crash_record(&mut self, enable: bool) -> &mut Self {
+        self.crash_record = enable;
+        self
+    }
+
+    /// Capture the reason for the last reset.
+    ///
+    /// When `enable` is `true`, the runtime copies the SRC reset status register into
+    /// the `.uninit` section during pre-init. The runtime doesn't clear the register.
+    /// The capture happens before the runtime changes clocks, and before it calls your
+    /// pre-init hook. In your firmware, use `imxrt_rt::reset_reason` to read the
+    /// reason, or `imxrt_rt::reset_status` to read the register's value.
+    ///
+    /// The register's flags accumulate across resets. To observe only the resets that
+    /// happen after this boot, call `imxrt_rt::clear_reset_status` once you've read
+    /// the status.
+    pub fn reset_reason(&mut self, enable: bool) -> &mut Self {
+        self.reset_reason = enable;
+        self
+    }
+
+    /// Check the sections that the runtime copies from flash.
+    ///
+    /// When `enable` is `true`, the builder adds an integrity table to the program.
+    /// After linking, use [`checksum_image`] to compute a CRC of each section in
+    /// the table. After the runtime copies the vector table, instructions, and
+    /// read-only data, it checks that each copy matches its CRC. If the post-link
+    /// step didn't compute the CRCs, the runtime skips the check. Deferred
+    /// sections aren't checked.
+    ///
+    /// If a copy doesn't match, the runtime calls `IntegrityCheckFailed`, passing
+    /// the section's start address. By default, `IntegrityCheckFailed` resets the
+    /// processor. You can define your own handler. It runs before static data is
+    /// initialized, so it can't use statics, and it should execute from flash.
+    ///
+    /// ```ignore
+    /// #[unsafe(no_mangle)]
+    /// #[unsafe(link_section = ".xip")]
+    /// unsafe extern "C" fn IntegrityCheckFailed(section: *const u32) -> ! {
+    ///     loop {}
+    /// }
+    /// ```
+    pub fn integrity_check(&mut self, enable: bool) -> &mut Self {
+        self.integrity_check = enable;
+        self
+    }
+
+    /// Call static constructors before `main()`.
+    ///
+    /// The linker script always collects `.preinit_array` and `.init_array`, and
+    /// it marks them with `__preinit_array_start`, `__preinit_array_end`,
+    /// `__init_array_start`, and `__init_array_end`. C and C++ libraries put their
+    /// static constructors in these arrays.
+    ///
+    /// When `enable` is `true`, the runtime's pre-init function initializes `.data`
+    /// and `.bss`, enables the FPU, and then calls each function in `.preinit_array`,
+    /// followed by each function in `.init_array`. `cortex-m-rt` then leaves `.data`
+    /// and `.bss` alone, so the constructors' work survives until `main()`.
+    ///
+    /// By default, the runtime doesn't call the functions. [`build()`](Self::build)
+    /// returns an error if you also [defer](Self::defer) instructions or read-only
+    /// data. Constructors can't execute from, or read, any other deferred section.
+    pub fn init_arrays(&mut self, enable: bool) -> &mut Self {
+        self.init_arrays = enable;
+        self
+    }
+
+    /// Keep the exception unwinding tables, and place them in `memory`.
+    ///
+    /// By default, the linker script discards `.ARM.exidx` and `.ARM.extab`. Call this
+    /// to keep them, so that your firmware can produce a backtrace after a panic or a
+    /// fault. The linker script marks the tables with `__exidx_start`, `__exidx_end`,
+    /// `__extab_start`, and `__extab_end`. If the program is loaded from flash, and
+    /// `memory` isn't flash, the runtime copies the tables into `memory`.
+    ///
+    /// Each table entry locates its function with a 31-bit offset, so the tables
+    /// must be within 1GiB of all code. Otherwise, the link fails. On the 10xx chips,
+    /// flash is too far from ITCM; if code executes from both, place the tables
+    /// in OCRAM.
+    ///
+    /// In your firmware, use `imxrt_rt::backtrace` to walk the stack with these
+    /// tables. Build with `-C force-unwind-tables=yes` so that the compiler produces
+    /// tables for your code, even when panics abort.
+    ///
+    /// ```no_run
+    /// use imxrt_rt::{Family, Memory, RuntimeBuilder};
+    ///
+    /// RuntimeBuilder::from_flexspi(Family::Imxrt1060, 16 * 1024 * 1024)
+    ///     .unwind_tables(Memory::Flash)
+    ///     .build()
+    ///     .unwrap();
+    /// ```
+    pub fn unwind_tables(&mut self, memory: Memory) -> &mut Self {
+        self.unwind_tables = Some(memory);
         self
     }
 
     /// Set the memory placement for code.
-    pub fn text(&mut self, memory: Memory) -> &mut Self {
-        self.text = memory;
+    ///
+    /// Supply a [`Memory`] to place all code in that memory. Supply a [`Placement`]
+    /// to let code spill into a second memory when it doesn't fit.
+    pub fn text(&mut self, placement: impl Into<Placement>) -> &mut Self {
+        self.text = placement.into();
         self
     }
     /// Set the memory placement for read-only data.
@@ -480,6 +1257,32 @@ impl RuntimeBuilder {
         self.vectors = memory;
         self
     }
+    /// Reserve vector table entries for at least `count` device interrupts.
+    ///
+    /// By default, the vector table has one entry for each interrupt in the device's
+    /// `__INTERRUPTS` table. If `count` is larger, the linker reserves the extra entries
+    /// behind the vector table, and the runtime points them at `DefaultHandler` during
+    /// pre-init. Use `imxrt_rt::register_interrupt` to install handlers at runtime.
+    ///
+    /// The builder aligns the vector table for its size. If `count` exceeds the number
+    /// of interrupts supported by the core, [`build()`](Self::build) returns an error.
+    pub fn interrupts(&mut self, count: usize) -> &mut Self {
+        self.interrupts = count;
+        self
+    }
+    /// Reserve space for a relocated vector table in `memory`.
+    ///
+    /// The reservation is as large as the vector table, and it has the vector table's
+    /// alignment. The runtime never initializes it. In your firmware, use
+    /// `imxrt_rt::relocate_vector_table_to_reserved` to copy the active vector table
+    /// into the reservation and point VTOR at the copy.
+    ///
+    /// By default, the builder doesn't reserve space. You can still relocate the vector
+    /// table into your own `imxrt_rt::VectorTable`.
+    pub fn relocated_vectors(&mut self, memory: Memory) -> &mut Self {
+        self.relocated_vectors = Some(memory);
+        self
+    }
     /// Set the memory placement for zero-initialized data.
     pub fn bss(&mut self, memory: Memory) -> &mut Self {
         self.bss = memory;
@@ -490,6 +1293,30 @@ impl RuntimeBuilder {
         self.uninit = memory;
         self
     }
+    /// Set the memory placement for retained data.
+    ///
+    /// The runtime never initializes the `.retained` section, so its contents
+    /// can survive a warm reset. Use `imxrt_rt::Retained` in your firmware to
+    /// detect if the contents survived.
+    ///
+    /// The section is placed after the vector table, if they share a memory.
+    /// Keep the memory map stable between programs that share retained data.
+    /// Note that the boot ROM may use parts of OCRAM, and it may clobber any
+    /// retained data in OCRAM.
+    pub fn retained(&mut self, memory: Memory) -> &mut Self {
+        self.retained = memory;
+        self
+    }
+    /// Reserve at least `bytes` for retained data.
+    ///
+    /// By default, the `.retained` section is only as large as its contents.
+    /// A reservation keeps the addresses of the following sections stable
+    /// as you add retained data. The linker signals an error if the contents
+    /// exceed the reservation.
+    pub fn retained_size(&mut self, bytes: usize) -> &mut Self {
+        self.retained_size = bytes;
+        self
+    }
     /// Set the memory placement for stack memory.
     pub fn stack(&mut self, memory: Memory) -> &mut Self {
         self.stack = memory;
@@ -540,21 +1367,249 @@ impl RuntimeBuilder {
         }
         self
     }
-
-    /// Set the name of the linker script file.
+    /// Compress the load images of instructions, read-only data, and data.
     ///
-    /// You can use this to customize the linker script name for your users.
-    /// See the [crate-level documentation](crate#linker-script) for more
-    /// information.
-    pub fn linker_script_name(&mut self, name: &str) -> &mut Self {
-        self.linker_script_name = name.into();
-        self
-    }
-
-    /// Set the name of the device's linker file that's included by the
-    /// runtime's linker script.
+    /// The linker lays out the uncompressed program in `uncompressed_size` bytes
+    /// of flash. This can be larger than your flash. After linking, use
+    /// [`compress_image`] to compress the program; it signals an error if the
+    /// compressed program doesn't fit in your flash. At boot, the runtime
+    /// decompresses the sections into their memories.
     ///
-    /// By default, the device PAC is expected to place a file named
+    /// ```no_run
+    /// use imxrt_rt::{Family, Memory, RuntimeBuilder};
+    ///
+    /// RuntimeBuilder::from_flexspi(Family::Imxrt1060, 1984 * 1024)
+    ///     .compress(4 * 1024 * 1024)
+    ///     .build()
+    ///     .unwrap();
+    /// ```
+    ///
+    /// Sections that execute, or are read, from flash can't be compressed. The
+    /// runtime only compresses the sections that follow the last such section. For
+    /// example, if read-only data stays in flash, then only data is compressed.
+    ///
+    /// If the program isn't compressed after linking, the runtime copies the sections
+    /// as usual. Compressed sections can't be [deferred](Self::defer). The boot header's
+    /// image size describes the uncompressed program.
+    ///
+    /// If this builder is not configuring a flash-loaded runtime, this
+    /// call is silently ignored.
+    pub fn compress(&mut self, uncompressed_size: usize) -> &mut Self {
+        if let Some(flash_opts) = &mut self.flash_opts {
+            flash_opts.uncompressed_size = Some(uncompressed_size);
+        }
+        self
+    }
+    /// Configure how the FlexSPI controller buffers AHB reads from flash.
+    ///
+    /// By default, the runtime keeps the boot ROM's AHB buffer configuration.
+    /// When you execute from flash, the buffer and prefetch configuration
+    /// affects performance.
+    ///
+    /// ```no_run
+    /// use imxrt_rt::{AhbRxBuffer, Family, FlexSpiAhb, RuntimeBuilder};
+    ///
+    /// RuntimeBuilder::from_flexspi(Family::Imxrt1060, 16 * 1024 * 1024)
+    ///     .flexspi_ahb(FlexSpiAhb {
+    ///         prefetch: true,
+    ///         buffers: vec![
+    ///             AhbRxBuffer { master_id: 0, size: 768, prefetch: true },
+    ///             AhbRxBuffer { master_id: 1, size: 256, prefetch: false },
+    ///         ],
+    ///     })
+    ///     .build()
+    ///     .unwrap();
+    /// ```
+    ///
+    /// The runtime can't reconfigure the controller while it executes from flash.
+    /// Instead, the runtime copies a small routine into ITCM, or into OCRAM if there's
+    /// no ITCM, and calls it before copying any sections. The routine waits for the
+    /// controller to idle, then applies the configuration and resets the controller's
+    /// buffers. The routine stays in memory, but it's only called once.
+    ///
+    /// The builder checks that the buffers fit in the 10xx chips' 1KiB of AHB RX
+    /// buffer memory. On the 11xx chips, check the buffer memory size in the reference
+    /// manual. This isn't available for the 1180. If the configuration is invalid,
+    /// [`build()`](Self::build) returns an error.
+    ///
+    /// If this builder is not configuring a flash-loaded runtime, this
+    /// call is silently ignored.
+    pub fn flexspi_ahb(&mut self, ahb: FlexSpiAhb) -> &mut Self {
+        if let Some(flash_opts) = &mut self.flash_opts {
+            flash_opts.ahb = Some(ahb);
+        }
+        self
+    }
+
+    /// Place input sections matching `pattern` into `memory`.
+    ///
+    /// `pattern` is a linker script input section description, like
+    /// `*libarm_cortexM7lfsp_math.a:*(.text .text.*)`. The builder gives each
+    /// pattern its own output section, and those output sections precede
+    /// the runtime's catch-all sections. Therefore, matching input sections
+    /// are placed in `memory` instead of the memory selected for their kind
+    /// of section. Patterns are matched in the order they're added.
+    ///
+    /// If the program is loaded from flash, and `memory` isn't flash, the runtime
+    /// copies the input sections from flash into `memory`. Use this for code,
+    /// read-only data, and initialized data. Don't use this to place zero-initialized
+    /// or uninitialized sections; those sections would occupy space in flash, and the
+    /// runtime would copy them like initialized data.
+    ///
+    /// ```no_run
+    /// use imxrt_rt::{Family, Memory, RuntimeBuilder};
+    ///
+    /// RuntimeBuilder::from_flexspi(Family::Imxrt1060, 16 * 1024 * 1024)
+    ///     .text(Memory::Flash)
+    ///     .place_input("*libarm_cortexM7lfsp_math.a:*(.text .text.*)", Memory::Itcm)
+    ///     .build()
+    ///     .unwrap();
+    /// ```
+    pub fn place_input(&mut self, pattern: &str, memory: Memory) -> &mut Self {
+        self.input_placements.push(InputPlacement {
+            pattern: pattern.into(),
+            memory,
+        });
+        self
+    }
+
+    /// Place the section `name` at a fixed `address`.
+    ///
+    /// `name` is the name of the section, like `.mailbox`. The builder creates an
+    /// output section that keeps all input sections named `name`, or starting with
+    /// `name` followed by a period. The output section starts at `address`, and it
+    /// can hold at most `size` bytes. Use `kind` to describe how the runtime initializes
+    /// the section.
+    ///
+    /// The builder removes the fixed section from the memory region that contains it,
+    /// so that nothing else overlaps the fixed section. If the fixed section is in the
+    /// middle of the region, the region shrinks to the larger portion on either side of
+    /// the fixed section; the runtime doesn't use the smaller portion.
+    ///
+    /// ```no_run
+    /// use imxrt_rt::{Family, FixedSectionKind, RuntimeBuilder};
+    ///
+    /// RuntimeBuilder::from_flexspi(Family::Imxrt1170, 16 * 1024 * 1024)
+    ///     .fixed_section(".mailbox", 0x202C_0000, 256, FixedSectionKind::NoLoad)
+    ///     .build()
+    ///     .unwrap();
+    /// ```
+    ///
+    /// In your firmware, place objects in the section with `link_section`.
+    ///
+    /// ```ignore
+    /// #[unsafe(link_section = ".mailbox")]
+    /// static mut MAILBOX: [u32; 64] = [0; 64];
+    /// ```
+    ///
+    /// # Errors
+    ///
+    /// [`build()`](Self::build) returns an error if the fixed section isn't
+    /// within one of the memory regions, or if fixed sections overlap. The linker
+    /// signals an error if the contents of the section exceed `size`.
+    pub fn fixed_section(
+        &mut self,
+        name: &str,
+        address: u32,
+        size: usize,
+        kind: FixedSectionKind,
+    ) -> &mut Self {
+        self.fixed_sections.push(FixedSection {
+            name: name.into(),
+            address,
+            size,
+            kind,
+        });
+        self
+    }
+
+    /// Align the start and end of `section` to `bytes`.
+    ///
+    /// `bytes` must be a power of two. Use this to align sections to cache lines, or to
+    /// MPU region sizes. By default, the sections that the runtime copies or zeroes,
+    /// including input placements, are 32-byte aligned. The stack is 8-byte aligned, and
+    /// all other sections are 4-byte aligned. An alignment smaller than the default has
+    /// no effect.
+    ///
+    /// The end of the section is padded to the alignment, so the padding counts toward
+    /// the section's size. For the stack and heap, the size rounds up to a multiple of
+    /// the alignment.
+    ///
+    /// ```no_run
+    /// use imxrt_rt::{Family, RuntimeBuilder, Section};
+    ///
+    /// RuntimeBuilder::from_flexspi(Family::Imxrt1060, 16 * 1024 * 1024)
+    ///     .align(Section::Bss, 32)
+    ///     .align(Section::Uninit, 32)
+    ///     .build()
+    ///     .unwrap();
+    /// ```
+    ///
+    /// # Errors
+    ///
+    /// [`build()`](Self::build) returns an error if `bytes` isn't a power of two, or if
+    /// a [`Custom`](Section::Custom) section doesn't describe a section you defined.
+    /// If a fixed section's address isn't aligned, `build()` returns an error.
+    pub fn align(&mut self, section: Section, bytes: usize) -> &mut Self {
+        self.alignments.retain(|(sec, _)| *sec != section);
+        self.alignments.push((section, bytes));
+        self
+    }
+
+    /// Defer copying `section` until after `main()` starts.
+    ///
+    /// Normally, the runtime copies sections from flash before `main()`. A deferred
+    /// section stays in flash until your firmware calls `imxrt_rt::load_deferred`.
+    /// Your firmware may also copy the section itself, perhaps using DMA; see
+    /// `imxrt_rt::deferred_loads` for the source and destination of each copy.
+    ///
+    /// You can defer [`Text`](Section::Text), [`Rodata`](Section::Rodata), and
+    /// [`Custom`](Section::Custom) sections that are copied from flash. Until the
+    /// section is loaded, your firmware must not use its contents. Place the code that
+    /// runs before the load in the `.xip` section, or in a section that isn't deferred.
+    /// If you defer [`Text`](Section::Text), this includes `main()`. The loader itself
+    /// executes in place.
+    ///
+    /// ```no_run
+    /// use imxrt_rt::{Family, Memory, RuntimeBuilder, Section};
+    ///
+    /// const PATTERN: &str = "*libdsp.a:*(.text .text.*)";
+    /// RuntimeBuilder::from_flexspi(Family::Imxrt1060, 16 * 1024 * 1024)
+    ///     .place_input(PATTERN, Memory::Itcm)
+    ///     .defer(Section::Custom(PATTERN.into()))
+    ///     .build()
+    ///     .unwrap();
+    /// ```
+    ///
+    /// If the program isn't loaded from flash, the sections are already at their
+    /// destination, and loading them has no effect.
+    ///
+    /// # Errors
+    ///
+    /// [`build()`](Self::build) returns an error if `section` can't be deferred, or if
+    /// a [`Custom`](Section::Custom) section doesn't describe an input placement or an
+    /// initialized fixed section.
+    pub fn defer(&mut self, section: Section) -> &mut Self {
+        if !self.deferred.contains(&section) {
+            self.deferred.push(section);
+        }
+        self
+    }
+
+    /// Set the name of the linker script file.
+    ///
+    /// You can use this to customize the linker script name for your users.
+    /// See the [crate-level documentation](crate#linker-script) for more
+    /// information.
+    pub fn linker_script_name(&mut self, name: &str) -> &mut Self {
+        self.linker_script_name = name.into();
+        self
+    }
+
+    /// Set the name of the device's linker file that's included by the
+    /// runtime's linker script.
+    ///
+    /// By default, the device PAC is expected to place a file named
     /// `device.x` on the linker search path. The runtime's linker script
     /// includes that `device.x` file when the "device" crate feature is
     /// enabled.
@@ -581,6 +1636,7 @@ impl RuntimeBuilder {
     /// - vectors
     /// - bss
     /// - uninit
+    /// - retained
     /// - stack
     /// - heap
     ///
@@ -601,6 +1657,12 @@ impl RuntimeBuilder {
         let mut in_memory = Vec::new();
         self.write_linker_script(&mut in_memory)?;
         fs::write(out_dir.join(&self.linker_script_name), &in_memory)?;
+
+        // Spilling requires that the linker can assign sections to a later
+        // output section when a memory region overflows.
+        if self.text.spill().is_some() {
+            println!("cargo:rustc-link-arg=--enable-non-contiguous-regions");
+        }
         Ok(())
     }
 
@@ -621,10 +1683,17 @@ impl RuntimeBuilder {
         &self,
         writer: &mut dyn Write,
     ) -> Result<(), Box<dyn std::error::Error>> {
-        self.check_configurations()?;
+        let flexram_layout = self.resolve_flexram_layout()?;
+        self.check_configurations(&flexram_layout)?;
 
         if let Some(flash_opts) = &self.flash_opts {
-            write_flash_memory_map(writer, self.family, flash_opts, &self.flexram_layout)?;
+            write_flash_memory_map(
+                writer,
+                self.family,
+                flash_opts,
+                &flexram_layout,
+                &self.fixed_sections,
+            )?;
 
             if flash_opts.is_boot_image() {
                 let boot_header_x = match self.family {
@@ -642,7 +1711,7 @@ impl RuntimeBuilder {
                 writer.write_all(boot_header_x)?;
             }
         } else {
-            write_ram_memory_map(writer, self.family, &self.flexram_layout)?;
+            write_ram_memory_map(writer, self.family, &flexram_layout, &self.fixed_sections)?;
         }
 
         if cfg!(feature = "device") {
@@ -654,29 +1723,140 @@ impl RuntimeBuilder {
         // sections. Then, the user specifies the actual placement through
         // the builder. This saves us the step of actually generating SECTION
         // commands.
-        region_alias(writer, "TEXT", self.text)?;
+        region_alias(writer, "TEXT", self.text.preferred())?;
+        // Without a fallback, the spill section is always empty.
+        let text_spill = self.text.spill().unwrap_or(self.text.preferred());
+        region_alias(writer, "TEXT_SPILL", text_spill)?;
         region_alias(writer, "VTABLE", self.vectors)?;
         region_alias(writer, "RODATA", self.rodata)?;
         region_alias(writer, "DATA", self.data)?;
         region_alias(writer, "BSS", self.bss)?;
         region_alias(writer, "UNINIT", self.uninit)?;
+        region_alias(writer, "RETAINED", self.retained)?;
 
         region_alias(writer, "STACK", self.stack)?;
         region_alias(writer, "HEAP", self.heap)?;
         // Used in the linker script and / or target code.
         writeln!(writer, "__stack_size = {:#010X};", self.stack_size.read()?)?;
         writeln!(writer, "__heap_size = {:#010X};", self.heap_size.read()?)?;
+        writeln!(writer, "__retained_size = {:#010X};", self.retained_size)?;
+        writeln!(writer, "__interrupt_count = {:#X};", self.interrupts)?;
+        writeln!(
+            writer,
+            "__vector_table_align = {:#X};",
+            vector_table_align(self.interrupts)
+        )?;
+        // The target runtime only records boot timings if the record has a size.
+        writeln!(
+            writer,
+            "__boot_timings_size = {:#X};",
+            if self.boot_timings {
+                BOOT_TIMINGS_SIZE
+            } else {
+                0
+            }
+        )?;
+        // The crash record is reserved in the retained section. Its handler replaces
+        // the default HardFault handler, unless the user defines HardFault.
+        writeln!(
+            writer,
+            "__crash_record_size = {:#X};",
+            if self.crash_record {
+                CRASH_RECORD_SIZE
+            } else {
+                0
+            }
+        )?;
+        // The crash record's handler runs on its own stack, since the main stack
+        // may have overflowed.
+        writeln!(
+            writer,
+            "__crash_stack_size = {:#X};",
+            if self.crash_record {
+                CRASH_STACK_SIZE
+            } else {
+                0
+            }
+        )?;
+        // The target runtime only captures the reset status if the record has a size.
+        let reset_status_address = if self.reset_reason {
+            self.family.reset_status_address()
+        } else {
+            0
+        };
+        writeln!(
+            writer,
+            "__reset_reason_size = {:#X};",
+            if self.reset_reason { 4 } else { 0 }
+        )?;
+        writeln!(writer, "__src_srsr = {reset_status_address:#010X};")?;
+        writeln!(
+            writer,
+            "__imxrt_rt_hard_fault = {};",
+            if self.crash_record {
+                "__imxrt_rt_crash_hard_fault"
+            } else {
+                "HardFault_"
+            }
+        )?;
+        // The target runtime skips these copies if they're deferred or compressed.
+        let compressed = self.compressed_sections();
+        writeln!(
+            writer,
+            "__skip_text_copy = {:#010X};",
+            u32::from(
+                self.is_deferred(&Section::Text)
+                    || compressed.contains(&".text")
+                    || compressed.contains(&".text_spill")
+            )
+        )?;
+        writeln!(
+            writer,
+            "__skip_rodata_copy = {:#010X};",
+            u32::from(self.is_deferred(&Section::Rodata) || compressed.contains(&".rodata"))
+        )?;
+        // When set, the runtime decompresses .data before cortex-m-rt would copy it.
+        writeln!(
+            writer,
+            "__data_compressed = {:#010X};",
+            u32::from(compressed.contains(&".data"))
+        )?;
+        // When set, the runtime initializes .data and .bss before cortex-m-rt would.
+        writeln!(
+            writer,
+            "__init_statics = {:#010X};",
+            u32::from(self.init_arrays || self.boot_timings)
+        )?;
+        // When set, the runtime calls static constructors after initializing statics.
+        writeln!(
+            writer,
+            "__call_init_arrays = {:#010X};",
+            u32::from(self.init_arrays)
+        )?;
+        for (section, symbol) in [
+            (Section::Text, "__text_align"),
+            (Section::Rodata, "__rodata_align"),
+            (Section::Data, "__data_align"),
+            (Section::Bss, "__bss_align"),
+            (Section::Uninit, "__uninit_align"),
+            (Section::Stack, "__stack_align"),
+            (Section::Heap, "__heap_align"),
+        ] {
+            writeln!(writer, "{symbol} = {:#X};", self.alignment(&section))?;
+        }
 
         if self.flash_opts.is_some() {
             // Runtime will see different VMA and LMA, and copy the sections.
             region_alias(writer, "LOAD_VTABLE", Memory::Flash)?;
             region_alias(writer, "LOAD_TEXT", Memory::Flash)?;
+            region_alias(writer, "LOAD_TEXT_SPILL", Memory::Flash)?;
             region_alias(writer, "LOAD_RODATA", Memory::Flash)?;
             region_alias(writer, "LOAD_DATA", Memory::Flash)?;
         } else {
             // When the VMA and LMA are equal, the runtime performs no copies.
             region_alias(writer, "LOAD_VTABLE", self.vectors)?;
-            region_alias(writer, "LOAD_TEXT", self.text)?;
+            region_alias(writer, "LOAD_TEXT", self.text.preferred())?;
+            region_alias(writer, "LOAD_TEXT_SPILL", text_spill)?;
             region_alias(writer, "LOAD_RODATA", self.rodata)?;
             region_alias(writer, "LOAD_DATA", self.data)?;
         }
@@ -685,33 +1865,559 @@ impl RuntimeBuilder {
         writeln!(
             writer,
             "__flexram_config = {:#010X};",
-            flexram_config(self.family, &self.flexram_layout)
+            flexram_config(self.family, &flexram_layout)
         )?;
+        // When set, the target runtime doesn't touch the FlexRAM configuration.
+        writeln!(
+            writer,
+            "__flexram_from_fuses = {:#010X};",
+            u32::from(self.flexram_fuses.is_some())
+        )?;
+        // The target runtime only touches the TCM size fields if the mask is non-zero.
+        let (tcm_size_mask, tcm_size_config) = if self.restrict_tcm_sizes {
+            let config = self
+                .family
+                .tcm_size_config(&flexram_layout)
+                .expect("Already checked");
+            (TCM_SIZE_MASK, config)
+        } else {
+            (0, 0)
+        };
+        writeln!(writer, "__tcm_size_mask = {tcm_size_mask:#010X};")?;
+        writeln!(writer, "__tcm_size_config = {tcm_size_config:#010X};")?;
+        // The target runtime only changes the core clock if the divider is non-zero.
+        let core_clock = self
+            .core_clock_mhz
+            .map(|mhz| self.family.core_clock_config(mhz).expect("Already checked"))
+            .unwrap_or_default();
+        writeln!(
+            writer,
+            "__arm_pll_div_select = {:#010X};",
+            core_clock.div_select
+        )?;
+        writeln!(writer, "__arm_podf = {:#010X};", core_clock.arm_podf)?;
+        writeln!(writer, "__ipg_podf = {:#010X};", core_clock.ipg_podf)?;
+        writeln!(writer, "__dcdc_trg = {:#010X};", core_clock.dcdc_trg)?;
+        // The target runtime only configures the FlexSPI AHB buffers if it copies
+        // the configuration routine.
+        let ahb = self
+            .flash_opts
+            .as_ref()
+            .and_then(|flash_opts| Some((flash_opts.flexspi, flash_opts.ahb.as_ref()?)));
+        let (flexspi_address, ahbcr, buffers) = match ahb {
+            Some((flexspi, ahb)) => (
+                flexspi
+                    .register_address(self.family)
+                    .expect("Already checked"),
+                u32::from(ahb.prefetch) << 5,
+                ahb.buffer_config().collect(),
+            ),
+            None => (0, 0, Vec::new()),
+        };
+        writeln!(writer, "__flexspi_address = {flexspi_address:#010X};")?;
+        writeln!(writer, "__flexspi_ahbcr_prefetch = {ahbcr:#010X};")?;
+        let (buffer_count, _) = FlexSpiAhb::limits(self.family);
+        writeln!(writer, "__flexspi_ahbrxbuf_count = {buffer_count};")?;
+        for idx in 0..8 {
+            let config = buffers.get(idx).copied().unwrap_or(0);
+            writeln!(writer, "__flexspi_ahbrxbuf{idx}cr0 = {config:#010X};")?;
+        }
+        // The target runtime configures the watchdogs with these values.
+        for (watchdog, symbol, toval_symbol) in [
+            (Watchdog::Wdog1, "__wdog1_config", "__wdog1_toval"),
+            (Watchdog::Wdog2, "__wdog2_config", "__wdog2_toval"),
+        ] {
+            let (config, toval) = self.wdog_config(watchdog).expect("Already checked");
+            writeln!(writer, "{symbol} = {config:#010X};")?;
+            writeln!(writer, "{toval_symbol} = {toval:#010X};")?;
+        }
+        let (rtwdog_config, rtwdog_toval) = self
+            .watchdog_mode(Watchdog::Rtwdog)
+            .rtwdog_config()
+            .expect("Already checked");
+        writeln!(writer, "__rtwdog_config = {rtwdog_config:#010X};")?;
+        writeln!(writer, "__rtwdog_toval = {rtwdog_toval:#010X};")?;
         // The target runtime looks at this value to predicate some pre-init instructions.
-        // Could be helpful for binary identification, but it's an undocumented feature.
+        // Referenced in target code, and helpful for binary identification.
         writeln!(writer, "__imxrt_rt_v0.2 = {:#010X};", self.family.id(),)?;
+        // Referenced in target code.
+        writeln!(
+            writer,
+            "__imxrt_rt_placements = {:#010X};",
+            self.placements()
+        )?;
 
-        let link_x = include_bytes!("host/imxrt-link.x");
-        writer.write_all(link_x)?;
+        // Fixed sections must land at their requested address.
+        for FixedSection {
+            name,
+            address,
+            size,
+            ..
+        } in &self.fixed_sections
+        {
+            writeln!(
+                writer,
+                "ASSERT(ADDR({name}) == {address:#X} && SIZEOF({name}) <= {size:#X}, \"
+ERROR(imxrt-rt): fixed section {name} must be at {address:#X}, and it cannot exceed {size} bytes\");"
+            )?;
+        }
+
+        let link_x = include_str!("host/imxrt-link.x")
+            .replace(COPY_TABLE_MARKER, &self.copy_table())
+            .replace(DEFERRED_TABLE_MARKER, &self.deferred_table())
+            .replace(COMPRESSION_TABLE_MARKER, &self.compression_table())
+            .replace(INTEGRITY_TABLE_MARKER, &self.integrity_table())
+            .replace(CRC_TABLE_MARKER, &self.crc_table())
+            .replace(
+                OUTPUT_SECTIONS_MARKER,
+                &self.output_sections(&flexram_layout),
+            );
+        writer.write_all(link_x.as_bytes())?;
 
         Ok(())
     }
 
+    /// Returns the alignment of `section`, in bytes.
+    fn alignment(&self, section: &Section) -> usize {
+        self.alignments
+            .iter()
+            .find(|(sec, _)| sec == section)
+            .map_or(0, |(_, bytes)| *bytes)
+            .max(section.default_alignment())
+    }
+
+    /// Returns the memory that holds the load image of sections placed in `memory`.
+    fn load_memory(&self, memory: Memory) -> Memory {
+        if self.flash_opts.is_some() {
+            Memory::Flash
+        } else {
+            memory
+        }
+    }
+
+    /// Produce the names of the output sections generated for input placements.
+    fn input_placement_names(&self) -> impl Iterator<Item = String> + '_ {
+        (0..self.input_placements.len()).map(|idx| format!(".placed_input_{idx}"))
+    }
+
+    /// Produce the sections, besides the runtime's sections, that are copied
+    /// from their load address.
+    ///
+    /// Each item is the section's name, as known by [`Section::Custom`], and
+    /// the name of its output section.
+    fn copied_sections(&self) -> impl Iterator<Item = (&str, String)> + '_ {
+        let placements = self
+            .input_placements
+            .iter()
+            .map(|placement| placement.pattern.as_str())
+            .zip(self.input_placement_names());
+        let fixed = self
+            .fixed_sections
+            .iter()
+            .filter(|fixed| fixed.kind == FixedSectionKind::Initialized)
+            .map(|fixed| (fixed.name.as_str(), fixed.name.clone()));
+        placements.chain(fixed)
+    }
+
+    /// What the runtime does with `watchdog`.
+    fn watchdog_mode(&self, watchdog: Watchdog) -> WatchdogMode {
+        let configured = self
+            .watchdogs
+            .iter()
+            .find(|(wdog, _)| *wdog == watchdog)
+            .map(|(_, mode)| *mode);
+        configured.unwrap_or(match (watchdog, self.family) {
+            (
+                Watchdog::Rtwdog,
+                Family::Imxrt1010
+                | Family::Imxrt1015
+                | Family::Imxrt1020
+                | Family::Imxrt1040
+                | Family::Imxrt1050
+                | Family::Imxrt1060
+                | Family::Imxrt1064
+                | Family::Imxrt1160
+                | Family::Imxrt1170,
+            )
+            | (Watchdog::Wdog1 | Watchdog::Wdog2, Family::Imxrt1180) => WatchdogMode::Disable,
+            (Watchdog::Rtwdog, Family::Imxrt1180) | (Watchdog::Wdog1 | Watchdog::Wdog2, _) => {
+                WatchdogMode::Keep
+            }
+        })
+    }
+
+    /// The configuration for WDOG1 or WDOG2.
+    ///
+    /// The second value is for RTWDOG[TOVAL]. It's only non-zero on the 1180,
+    /// where WDOG1 and WDOG2 are RTWDOGs.
+    fn wdog_config(&self, watchdog: Watchdog) -> Result<(u32, u32), String> {
+        let mode = self.watchdog_mode(watchdog);
+        match self.family {
+            Family::Imxrt1010
+            | Family::Imxrt1015
+            | Family::Imxrt1020
+            | Family::Imxrt1040
+            | Family::Imxrt1050
+            | Family::Imxrt1060
+            | Family::Imxrt1064
+            | Family::Imxrt1160
+            | Family::Imxrt1170 => mode.wdog_config().map(|config| (config, 0)),
+            Family::Imxrt1180 => mode.rtwdog_config(),
+        }
+    }
+
+    /// Returns `true` if the runtime doesn't copy `section` before `main()`.
+    fn is_deferred(&self, section: &Section) -> bool {
+        self.deferred.contains(section)
+    }
+
+    /// Generate the entries of the copy table.
+    ///
+    /// Each entry has the start and end VMA of an output section, followed
+    /// by the LMA of the output section. The target runtime copies these
+    /// sections.
+    fn copy_table(&self) -> String {
+        let unwind_tables = self
+            .unwind_tables
+            .iter()
+            .flat_map(|_| [".ARM.extab", ".ARM.exidx"])
+            .map(String::from);
+        self.copied_sections()
+            .filter(|(custom, _)| !self.is_deferred(&Section::Custom((*custom).into())))
+            .map(|(_, name)| name)
+            .chain(unwind_tables)
+            .map(|name| {
+                format!(
+                    "LONG(ADDR({name})); LONG(ADDR({name}) + SIZEOF({name})); LONG(LOADADDR({name}));"
+                )
+            })
+            .collect::<Vec<_>>()
+            .join("\n    ")
+    }
+
+    /// Encode the memory of each section.
+    ///
+    /// Two bits describe each section's memory. Keep the order in sync with
+    /// the target runtime.
+    fn placements(&self) -> u32 {
+        [
+            self.text.preferred(),
+            self.text.spill().unwrap_or(self.text.preferred()),
+            self.rodata,
+            self.data,
+            self.vectors,
+            self.bss,
+            self.uninit,
+            self.retained,
+            self.stack,
+            self.heap,
+        ]
+        .into_iter()
+        .enumerate()
+        .fold(0, |placements, (idx, memory)| {
+            placements | memory.id() << (2 * idx)
+        })
+    }
+
+    /// Generate the entries of the deferred table.
+    ///
+    /// Each entry starts with a key that identifies the [`Section`]. The remainder
+    /// of the entry is the same as a copy table entry. The target runtime copies
+    /// these sections when the user asks.
+    fn deferred_table(&self) -> String {
+        let mut entries = Vec::new();
+        if self.is_deferred(&Section::Text) {
+            entries.push((".text", ".text".to_string()));
+            entries.push((".text", ".text_spill".to_string()));
+        }
+        if self.is_deferred(&Section::Rodata) {
+            entries.push((".rodata", ".rodata".to_string()));
+        }
+        entries.extend(
+            self.copied_sections()
+                .filter(|(custom, _)| self.is_deferred(&Section::Custom((*custom).into()))),
+        );
+        entries
+            .into_iter()
+            .map(|(key, name)| {
+                format!(
+                    "LONG({key:#010X}); LONG(ADDR({name})); LONG(ADDR({name}) + SIZEOF({name})); LONG(LOADADDR({name}));",
+                    key = deferred_key(key)
+                )
+            })
+            .collect::<Vec<_>>()
+            .join("\n    ")
+    }
+
+    /// Produce the output sections whose load images are compressed.
+    ///
+    /// These are the sections that follow the last section that's used from flash.
+    /// They're ordered by their load address.
+    fn compressed_sections(&self) -> Vec<&'static str> {
+        if self
+            .flash_opts
+            .as_ref()
+            .is_none_or(|flash_opts| flash_opts.uncompressed_size.is_none())
+        {
+            return Vec::new();
+        }
+        let sections = [
+            (".text", self.text.preferred()),
+            (
+                ".text_spill",
+                self.text.spill().unwrap_or(self.text.preferred()),
+            ),
+            (".rodata", self.rodata),
+            (".data", self.data),
+        ];
+        let first = sections
+            .iter()
+            .rposition(|(_, memory)| *memory == Memory::Flash)
+            .map_or(0, |idx| idx + 1);
+        sections[first..].iter().map(|(name, _)| *name).collect()
+    }
+
+    /// Generate the compression table.
+    ///
+    /// The table has a header, followed by an entry for each compressed section.
+    /// The header has a magic number, the table's address, the compression state,
+    /// the end of flash, and the number of entries. Each entry has the start and end VMA of an output
+    /// section, followed by the LMA and the size of its (possibly compressed)
+    /// load image. See [`compress_image`] for more information.
+    fn compression_table(&self) -> String {
+        let compressed = self.compressed_sections();
+        let Some(flash_opts) = self.flash_opts.as_ref().filter(|_| !compressed.is_empty()) else {
+            return String::new();
+        };
+        let flash_end = flash_opts
+            .flash_origin(self.family)
+            .expect("Already checked") as usize
+            + flash_opts.size;
+        let mut table = vec![format!(
+            "LONG({COMPRESSION_MAGIC:#010X}); LONG(__scompression_table); LONG(0); LONG({flash_end:#X}); LONG({});",
+            compressed.len()
+        )];
+        table.extend(compressed.iter().map(|name| {
+            format!(
+                "LONG(ADDR({name})); LONG(ADDR({name}) + SIZEOF({name})); LONG(LOADADDR({name})); LONG(SIZEOF({name}));"
+            )
+        }));
+        table.join("\n    ")
+    }
+
+    /// Generate the integrity table.
+    ///
+    /// The table has a header, followed by an entry for each checked section. The
+    /// header has a magic number, the table's address, the checksum state, and the
+    /// number of entries. Each entry has the start and end VMA of an output section,
+    /// followed by its LMA and its CRC. See [`checksum_image`] for more information.
+    fn integrity_table(&self) -> String {
+        if !self.integrity_check {
+            return String::new();
+        }
+        let mut checked = vec![".vector_table"];
+        if !self.is_deferred(&Section::Text) {
+            checked.extend([".text", ".text_spill"]);
+        }
+        if !self.is_deferred(&Section::Rodata) {
+            checked.push(".rodata");
+        }
+        let mut table = vec![format!(
+            "LONG({INTEGRITY_MAGIC:#010X}); LONG(__sintegrity_table); LONG(0); LONG({});",
+            checked.len()
+        )];
+        table.extend(checked.iter().map(|name| {
+            format!(
+                "LONG(ADDR({name})); LONG(ADDR({name}) + SIZEOF({name})); LONG(LOADADDR({name})); LONG(0);"
+            )
+        }));
+        table.join("\n    ")
+    }
+
+    /// Generate the CRC lookup table for the integrity check.
+    fn crc_table(&self) -> String {
+        if !self.integrity_check {
+            return String::new();
+        }
+        CRC32_TABLE
+            .chunks(4)
+            .map(|words| {
+                words
+                    .iter()
+                    .map(|word| format!("LONG({word:#010X});"))
+                    .collect::<Vec<_>>()
+                    .join(" ")
+            })
+            .collect::<Vec<_>>()
+            .join("\n    ")
+    }
+
+    /// Generate the output sections that precede the runtime's output sections.
+    fn output_sections(&self, flexram_layout: &[FlexRamKind]) -> String {
+        let mut sections = Vec::new();
+        // The FlexSPI AHB routine can't execute from flash.
+        if self
+            .flash_opts
+            .as_ref()
+            .is_some_and(|flash_opts| flash_opts.ahb.is_some())
+        {
+            let memory = if layout_count_of(FlexRamKind::Itcm, flexram_layout) > 0 {
+                Memory::Itcm
+            } else {
+                Memory::Ocram
+            };
+            sections.push(format!(
+                ".flexspi_ahb : ALIGN(4)
+  {{
+    __sflexspi_ahb = .;
+    KEEP(*(.imxrt_rt_flexspi_ahb));
+    . = ALIGN(4);
+    __eflexspi_ahb = .;
+  }} > {memory} AT> {load}
+  __siflexspi_ahb = LOADADDR(.flexspi_ahb);",
+                load = Memory::Flash,
+            ));
+        } else {
+            sections.push(
+                "/DISCARD/ : { *(.imxrt_rt_flexspi_ahb) }
+  __sflexspi_ahb = 0;
+  __eflexspi_ahb = 0;
+  __siflexspi_ahb = 0;"
+                    .into(),
+            );
+        }
+        // The unwinder finds the tables by these symbols. The linker won't keep the
+        // tables if any /DISCARD/ matches them, so only discard them here.
+        if let Some(memory) = self.unwind_tables {
+            sections.push(format!(
+                ".ARM.extab : ALIGN(4)
+  {{
+    __extab_start = .;
+    *(.ARM.extab .ARM.extab.* .gnu.linkonce.armextab.*)
+    . = ALIGN(4);
+    __extab_end = .;
+  }} > {memory} AT> {load}
+
+  .ARM.exidx : ALIGN(4)
+  {{
+    __exidx_start = .;
+    *(.ARM.exidx .ARM.exidx.* .gnu.linkonce.armexidx.*)
+    __exidx_end = .;
+  }} > {memory} AT> {load}",
+                load = self.load_memory(memory),
+            ));
+        } else {
+            sections.push(
+                "/DISCARD/ :
+  {
+    /* Unused exception related info that only wastes space */
+    *(.ARM.exidx);
+    *(.ARM.exidx.*);
+    *(.ARM.extab.*);
+  }
+  __extab_start = 0;
+  __extab_end = 0;
+  __exidx_start = 0;
+  __exidx_end = 0;"
+                    .into(),
+            );
+        }
+        // Sized like the runtime's vector table, including reserved interrupts.
+        if let Some(memory) = self.relocated_vectors {
+            sections.push(format!(
+                ".vector_table_relocated (NOLOAD) : ALIGN(__vector_table_align)
+  {{
+    __svector_table_relocated = .;
+    . += MAX(__evector_table, __evector_table_reserved) - __svector_table;
+    __evector_table_relocated = .;
+  }} > {memory}"
+            ));
+        } else {
+            sections.push(
+                "__svector_table_relocated = 0;
+  __evector_table_relocated = 0;"
+                    .into(),
+            );
+        }
+        for (name, placement) in self.input_placement_names().zip(&self.input_placements) {
+            let align = self
+                .alignment(&Section::Custom(placement.pattern.clone()))
+                .max(BURST_ALIGN);
+            sections.push(format!(
+                "{name} : ALIGN({align})
+  {{
+    FILL(0xff);
+    {pattern}
+    . = ALIGN({align});
+  }} > {memory} AT> {load}",
+                pattern = placement.pattern,
+                memory = placement.memory,
+                load = self.load_memory(placement.memory),
+            ));
+        }
+        let flash: Vec<_> = memory_regions(self.family, self.flash_opts.as_ref(), &[])
+            .into_iter()
+            .filter(|region| region.memory == Memory::Flash)
+            .collect();
+        for (idx, fixed) in self.fixed_sections.iter().enumerate() {
+            let name = &fixed.name;
+            let region = fixed_region_name(idx);
+            let align = self.alignment(&Section::Custom(name.clone()));
+            let (noload, load) = match fixed.kind {
+                FixedSectionKind::NoLoad => (" (NOLOAD)", String::new()),
+                FixedSectionKind::Initialized
+                    if !flash.is_empty() && !flash.iter().any(|region| region.contains(fixed)) =>
+                {
+                    ("", format!(" AT> {}", Memory::Flash))
+                }
+                FixedSectionKind::Initialized => ("", String::new()),
+            };
+            sections.push(format!(
+                "{name}{noload} : ALIGN({align})
+  {{
+    KEEP(*({name} {name}.*));
+    . = ALIGN({align});
+  }} > {region}{load}"
+            ));
+        }
+        sections.join("\n\n  ")
+    }
+
+    /// Produce the FlexRAM layout used to generate the memory map.
+    ///
+    /// If the user selected a fuse-defined configuration, this decodes
+    /// the fuse value.
+    fn resolve_flexram_layout(&self) -> Result<Vec<FlexRamKind>, String> {
+        match self.flexram_fuses {
+            Some(fuse_value) => self
+                .family
+                .flexram_banks_from_fuses(fuse_value)
+                .map(FlexRamBanks::to_flexram_layout)
+                .ok_or_else(|| {
+                    format!(
+                        "Chip {:?} does not support FlexRAM fuse value {fuse_value:#X}",
+                        self.family
+                    )
+                }),
+            None => Ok(self.flexram_layout.clone()),
+        }
+    }
+
     /// Implement i.MX RT specific sanity checks.
     ///
     /// This might not check everything! If the linker may detect a condition, we'll
     /// let the linker do that.
-    fn check_configurations(&self) -> Result<(), String> {
-        if self.family.flexram_bank_count() < self.flexram_layout.len() {
+    fn check_configurations(&self, flexram_layout: &[FlexRamKind]) -> Result<(), String> {
+        if self.family.flexram_bank_count() < flexram_layout.len() {
             return Err(format!(
                 "Chip {:?} only has {} total FlexRAM banks. Cannot allocate {:?}, a total of {} banks",
                 self.family,
                 self.family.flexram_bank_count(),
-                self.flexram_layout,
-                self.flexram_layout.len(),
+                flexram_layout,
+                flexram_layout.len(),
             ));
         }
-        let ocram_count = layout_count_of(FlexRamKind::Ocram, &self.flexram_layout);
+        let ocram_count = layout_count_of(FlexRamKind::Ocram, flexram_layout);
         if ocram_count < self.family.bootrom_ocram_banks() {
             return Err(format!(
                 "Chip {:?} requires at least {} OCRAM banks for the bootloader ROM",
@@ -728,6 +2434,13 @@ impl RuntimeBuilder {
             ));
         }
 
+        if self.restrict_tcm_sizes && self.family.tcm_size_config(flexram_layout).is_none() {
+            return Err(format!(
+                "Chip {:?} cannot restrict the TCM sizes",
+                self.family
+            ));
+        }
+
         fn prevent_flash(name: &str, memory: Memory) -> Result<(), String> {
             if memory == Memory::Flash {
                 Err(format!("Section '{name}' cannot be placed in flash"))
@@ -745,73 +2458,293 @@ impl RuntimeBuilder {
         prevent_flash!(vectors)?;
         prevent_flash!(bss)?;
         prevent_flash!(uninit)?;
+        prevent_flash!(retained)?;
         prevent_flash!(stack)?;
         prevent_flash!(heap)?;
+        if let Some(memory) = self.relocated_vectors {
+            prevent_flash("relocated_vectors", memory)?;
+        }
+
+        for (section, bytes) in &self.alignments {
+            if !bytes.is_power_of_two() {
+                return Err(format!(
+                    "Alignment of {section:?} must be a power of two, not {bytes}"
+                ));
+            }
+            if let Section::Custom(name) = section
+                && !self.fixed_sections.iter().any(|fixed| fixed.name == *name)
+                && !self
+                    .input_placements
+                    .iter()
+                    .any(|placement| placement.pattern == *name)
+            {
+                return Err(format!(
+                    "Cannot align {name}; it's not a fixed section or an input placement"
+                ));
+            }
+        }
+
+        if let Some(flash_opts) = &self.flash_opts
+            && let Some(ahb) = &flash_opts.ahb
+        {
+            if flash_opts.flexspi.register_address(self.family).is_none() {
+                return Err(format!(
+                    "Chip {:?} cannot configure the AHB buffers of {:?}",
+                    self.family, flash_opts.flexspi
+                ));
+            }
+            ahb.check(self.family)?;
+        }
+
+        if self.interrupts > self.family.max_interrupts() {
+            return Err(format!(
+                "Chip {:?} supports at most {} interrupts, not {}",
+                self.family,
+                self.family.max_interrupts(),
+                self.interrupts
+            ));
+        }
+
+        if let Some(mhz) = self.core_clock_mhz
+            && self.family.core_clock_config(mhz).is_none()
+        {
+            return Err(format!(
+                "Chip {:?} cannot run its core at {mhz}MHz",
+                self.family
+            ));
+        }
+
+        for watchdog in [Watchdog::Wdog1, Watchdog::Wdog2, Watchdog::Rtwdog] {
+            let mode = self.watchdog_mode(watchdog);
+            match watchdog {
+                Watchdog::Wdog1 | Watchdog::Wdog2 => self.wdog_config(watchdog).map(|_| ())?,
+                Watchdog::Rtwdog if self.family == Family::Imxrt1180 => {
+                    if mode != WatchdogMode::Keep {
+                        return Err(format!("Chip {:?} doesn't have {watchdog:?}", self.family));
+                    }
+                }
+                Watchdog::Rtwdog => mode.rtwdog_config().map(|_| ())?,
+            }
+        }
+
+        if self
+            .flash_opts
+            .as_ref()
+            .is_some_and(|flash_opts| flash_opts.uncompressed_size.is_some())
+        {
+            if self.family == Family::Imxrt1180 {
+                return Err(format!("Chip {:?} cannot compress its image", self.family));
+            }
+            let compressed = self.compressed_sections();
+            for (section, name) in [(Section::Text, ".text"), (Section::Rodata, ".rodata")] {
+                if self.is_deferred(&section) && compressed.contains(&name) {
+                    return Err(format!("Cannot defer {section:?}; it's compressed"));
+                }
+            }
+        }
+
+        if self.init_arrays {
+            for section in [Section::Text, Section::Rodata] {
+                if self.is_deferred(&section) {
+                    return Err(format!(
+                        "Cannot defer {section:?}; static constructors run before main()"
+                    ));
+                }
+            }
+        }
+
+        for section in &self.deferred {
+            match section {
+                Section::Text | Section::Rodata => {}
+                Section::Custom(name) => {
+                    if !self.copied_sections().any(|(custom, _)| custom == name) {
+                        return Err(format!(
+                            "Cannot defer {name}; it's not an initialized fixed section or an input placement"
+                        ));
+                    }
+                }
+                Section::Data | Section::Bss | Section::Uninit | Section::Stack | Section::Heap => {
+                    return Err(format!("Cannot defer {section:?}"));
+                }
+            }
+        }
+
+        let regions = memory_regions(self.family, self.flash_opts.as_ref(), flexram_layout);
+        for (idx, fixed) in self.fixed_sections.iter().enumerate() {
+            if fixed.size == 0 || !regions.iter().any(|region| region.contains(fixed)) {
+                return Err(format!(
+                    "Fixed section {} ({} bytes at {:#X}) is not within an allocated memory region",
+                    fixed.name, fixed.size, fixed.address
+                ));
+            }
+            let align = self.alignment(&Section::Custom(fixed.name.clone()));
+            if !fixed.address.is_multiple_of(align as u32) {
+                return Err(format!(
+                    "Fixed section {} at {:#X} is not {align}-byte aligned",
+                    fixed.name, fixed.address
+                ));
+            }
+            let start = fixed.address as usize;
+            if let Some(other) = self.fixed_sections[..idx].iter().find(|other| {
+                let other_start = other.address as usize;
+                start < other_start + other.size && other_start < start + fixed.size
+            }) {
+                return Err(format!(
+                    "Fixed sections {} and {} overlap",
+                    other.name, fixed.name
+                ));
+            }
+        }
 
         Ok(())
     }
 }
 
-/// Write RAM-like memory blocks.
+/// A memory region in the MEMORY command.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+struct MemoryRegion {
+    memory: Memory,
+    origin: usize,
+    length: usize,
+}
+
+impl MemoryRegion {
+    /// Returns `true` if the fixed section is entirely within this region.
+    fn contains(&self, fixed: &FixedSection) -> bool {
+        let start = fixed.address as usize;
+        self.origin <= start && start + fixed.size <= self.origin + self.length
+    }
+}
+
+/// Produce the RAM-like memory regions.
 ///
-/// Skips a section if there's no FlexRAM block allocated. If a user references one
-/// of this skipped sections, linking fails.
-fn write_flexram_memories(
-    output: &mut dyn Write,
-    family: Family,
-    flexram_layout: &[FlexRamKind],
-) -> io::Result<()> {
+/// Skips a region if there's no FlexRAM block allocated.
+fn flexram_regions(family: Family, flexram_layout: &[FlexRamKind]) -> Vec<MemoryRegion> {
     let itcm_count = layout_count_of(FlexRamKind::Itcm, flexram_layout);
     let dtcm_count = layout_count_of(FlexRamKind::Dtcm, flexram_layout);
     let ocram_count = layout_count_of(FlexRamKind::Ocram, flexram_layout);
 
+    let mut regions = Vec::new();
     if itcm_count > 0 {
-        let (itcm_start, itcm_size) = family.itcm_start_size(itcm_count);
-        writeln!(
-            output,
-            "ITCM (RWX) : ORIGIN = {itcm_start:#X}, LENGTH = {itcm_size:#X}"
-        )?;
+        let (origin, length) = family.itcm_start_size(itcm_count);
+        regions.push(MemoryRegion {
+            memory: Memory::Itcm,
+            origin,
+            length,
+        });
     }
     if dtcm_count > 0 {
-        writeln!(
-            output,
-            "DTCM (RWX) : ORIGIN = 0x20000000, LENGTH = {:#X}",
-            dtcm_count * family.flexram_bank_size(),
-        )?;
+        regions.push(MemoryRegion {
+            memory: Memory::Dtcm,
+            origin: 0x2000_0000,
+            length: dtcm_count * family.flexram_bank_size(),
+        });
     }
 
     let ocram_size = ocram_count * family.flexram_bank_size() + family.dedicated_ocram_size();
     if ocram_size > 0 {
+        regions.push(MemoryRegion {
+            memory: Memory::Ocram,
+            origin: family.ocram_start() as usize,
+            length: ocram_size,
+        });
+    }
+    regions
+}
+
+/// Produce all memory regions for the memory map.
+fn memory_regions(
+    family: Family,
+    flash_opts: Option<&FlashOpts>,
+    flexram_layout: &[FlexRamKind],
+) -> Vec<MemoryRegion> {
+    let flash = flash_opts.map(|flash_opts| MemoryRegion {
+        memory: Memory::Flash,
+        origin: flash_opts.flash_origin(family).expect("Already checked") as usize,
+        length: flash_opts.size,
+    });
+    flash
+        .into_iter()
+        .chain(flexram_regions(family, flexram_layout))
+        .collect()
+}
+
+/// Write a memory region, excluding the fixed sections it contains.
+///
+/// If a fixed section is in the middle of the region, the region shrinks
+/// to the larger portion on either side of the fixed section.
+fn write_memory_region(
+    output: &mut dyn Write,
+    region: MemoryRegion,
+    fixed_sections: &[FixedSection],
+) -> io::Result<()> {
+    let mut origin = region.origin;
+    let mut end = region.origin + region.length;
+    for fixed in fixed_sections {
+        let (start, stop) = (fixed.address as usize, fixed.address as usize + fixed.size);
+        if stop <= origin || end <= start {
+            continue;
+        }
+        if start.saturating_sub(origin) >= end.saturating_sub(stop) {
+            end = start;
+        } else {
+            origin = stop;
+        }
+    }
+    let attributes = if region.memory == Memory::Flash {
+        "RX"
+    } else {
+        "RWX"
+    };
+    writeln!(
+        output,
+        "{} ({attributes}) : ORIGIN = {origin:#X}, LENGTH = {:#X}",
+        region.memory,
+        end.saturating_sub(origin),
+    )
+}
+
+/// Write a memory region for each fixed section.
+fn write_fixed_memories(output: &mut dyn Write, fixed_sections: &[FixedSection]) -> io::Result<()> {
+    for (idx, fixed) in fixed_sections.iter().enumerate() {
         writeln!(
             output,
-            "OCRAM (RWX) : ORIGIN = {:#X}, LENGTH = {:#X}",
-            family.ocram_start(),
-            ocram_size,
+            "{} (RWX) : ORIGIN = {:#X}, LENGTH = {:#X}",
+            fixed_region_name(idx),
+            fixed.address,
+            fixed.size
         )?;
     }
     Ok(())
 }
 
+/// The name of the memory region that holds the fixed section at `idx`.
+fn fixed_region_name(idx: usize) -> String {
+    format!("FIXED_{idx}")
+}
+
 /// Generate a linker script MEMORY command that includes a FLASH block.
 fn write_flash_memory_map(
     output: &mut dyn Write,
     family: Family,
     flash_opts: &FlashOpts,
     flexram_layout: &[FlexRamKind],
+    fixed_sections: &[FixedSection],
 ) -> io::Result<()> {
     writeln!(
         output,
         "/* Memory map for '{:?}' with custom flash length {}. */",
         family, flash_opts.size
     )?;
-    writeln!(output, "MEMORY {{")?;
-    writeln!(
-        output,
-        "FLASH (RX) : ORIGIN = {:#X}, LENGTH = {:#X}",
-        flash_opts.flash_origin(family).expect("Already checked"),
-        flash_opts.size
-    )?;
-    write_flexram_memories(output, family, flexram_layout)?;
+    writeln!(output, "MEMORY {{")?;
+    for mut region in memory_regions(family, Some(flash_opts), flexram_layout) {
+        // A compressed image may be larger than flash before it's compressed.
+        if region.memory == Memory::Flash {
+            region.length = flash_opts.link_size();
+        }
+        write_memory_region(output, region, fixed_sections)?;
+    }
+    write_fixed_memories(output, fixed_sections)?;
     writeln!(output, "}}")?;
     writeln!(output, "__fcb_offset = {:#X};", family.fcb_offset())?;
     Ok(())
@@ -825,13 +2758,17 @@ fn write_ram_memory_map(
     output: &mut dyn Write,
     family: Family,
     flexram_layout: &[FlexRamKind],
+    fixed_sections: &[FixedSection],
 ) -> io::Result<()> {
     writeln!(
         output,
         "/* Memory map for '{family:?}' that executes from RAM. */",
     )?;
     writeln!(output, "MEMORY {{")?;
-    write_flexram_memories(output, family, flexram_layout)?;
+    for region in memory_regions(family, None, flexram_layout) {
+        write_memory_region(output, region, fixed_sections)?;
+    }
+    write_fixed_memories(output, fixed_sections)?;
     writeln!(output, "}}")?;
     Ok(())
 }
@@ -1103,6 +3040,196 @@ impl Family {
         };
         (itcm_start, itcm_size)
     }
+
+    /// Returns the register values that run the core at `mhz`.
+    ///
+    /// Returns `None` if the chip can't produce the frequency, or if the runtime
+    /// can't configure the chip's clocks.
+    fn core_clock_config(self, mhz: u32) -> Option<CoreClock> {
+        match self {
+            Family::Imxrt1040 | Family::Imxrt1050 | Family::Imxrt1060 | Family::Imxrt1064 => {
+                if mhz > 600 {
+                    return None;
+                }
+                // The ARM PLL runs at 12MHz * DIV_SELECT. ARM_PODF divides the PLL
+                // output for the core. Prefer the smallest divider.
+                let (div_select, arm_podf) = (1..=8).find_map(|podf| {
+                    let div_select = mhz * podf / 12;
+                    ((mhz * podf).is_multiple_of(12) && (54..=108).contains(&div_select))
+                        .then_some((div_select, podf))
+                })?;
+                Some(CoreClock {
+                    div_select,
+                    arm_podf: arm_podf - 1,
+                    ipg_podf: mhz.div_ceil(150) - 1,
+                    // 25mV steps above 800mV.
+                    dcdc_trg: if mhz > 528 { 0x12 } else { 0x0E },
+                })
+            }
+            Family::Imxrt1160 | Family::Imxrt1170 => {
+                let max_mhz = if self == Family::Imxrt1160 { 600 } else { 1000 };
+                if mhz > max_mhz {
+                    return None;
+                }
+                // The ARM PLL runs at 12MHz * DIV_SELECT / POST_DIV_SEL, where
+                // POST_DIV_SEL divides by 2, 4, or 8. Prefer the smallest divider.
+                let (div_select, post_div_sel) =
+                    [2, 4, 8]
+                        .into_iter()
+                        .zip(0..)
+                        .find_map(|(post_div, post_div_sel)| {
+                            let div_select = mhz * post_div / 12;
+                            ((mhz * post_div).is_multiple_of(12)
+                                && (104..=208).contains(&div_select))
+                            .then_some((div_select, post_div_sel))
+                        })?;
+                Some(CoreClock {
+                    div_select,
+                    arm_podf: post_div_sel,
+                    ipg_podf: 0,
+                    // 25mV steps above 600mV. Overdrive at 1.125V.
+                    dcdc_trg: if mhz > 600 { 0x15 } else { 0x10 },
+                })
+            }
+            Family::Imxrt1010 | Family::Imxrt1015 | Family::Imxrt1020 | Family::Imxrt1180 => None,
+        }
+    }
+
+    /// Returns the IOMUXC_GPR14 TCM size fields for this FlexRAM layout.
+    ///
+    /// Sizes round up to the next supported power of two. Returns `None`
+    /// if the chip doesn't have TCM size fields.
+    fn tcm_size_config(self, flexram_layout: &[FlexRamKind]) -> Option<u32> {
+        /// Encode a size, in bytes, into a CM7_CFGxTCMSZ field.
+        ///
+        /// 0 means no TCM, and 3 represents 4 KiB. Each increment doubles the size.
+        fn encode(size: usize) -> u32 {
+            if size == 0 {
+                0
+            } else {
+                size.max(4 * 1024).next_power_of_two().trailing_zeros() - 9
+            }
+        }
+        match self {
+            Family::Imxrt1010
+            | Family::Imxrt1015
+            | Family::Imxrt1020
+            | Family::Imxrt1040
+            | Family::Imxrt1050
+            | Family::Imxrt1060
+            | Family::Imxrt1064 => {
+                let itcm_size =
+                    layout_count_of(FlexRamKind::Itcm, flexram_layout) * self.flexram_bank_size();
+                let dtcm_size =
+                    layout_count_of(FlexRamKind::Dtcm, flexram_layout) * self.flexram_bank_size();
+                Some(encode(dtcm_size) << 20 | encode(itcm_size) << 16)
+            }
+            // TCM sizes follow the FlexRAM configuration; there's no
+            // separate control.
+            Family::Imxrt1160 | Family::Imxrt1170 | Family::Imxrt1180 => None,
+        }
+    }
+
+    /// Returns the number of device interrupts supported by the NVIC.
+    fn max_interrupts(self) -> usize {
+        match self {
+            Family::Imxrt1010
+            | Family::Imxrt1015
+            | Family::Imxrt1020
+            | Family::Imxrt1040
+            | Family::Imxrt1050
+            | Family::Imxrt1060
+            | Family::Imxrt1064
+            | Family::Imxrt1160
+            | Family::Imxrt1170 => 496,
+            // Armv8-M Mainline.
+            Family::Imxrt1180 => 480,
+        }
+    }
+
+    /// Returns the address of the SRC reset status register (SRSR).
+    fn reset_status_address(self) -> u32 {
+        match self {
+            Family::Imxrt1010
+            | Family::Imxrt1015
+            | Family::Imxrt1020
+            | Family::Imxrt1040
+            | Family::Imxrt1050
+            | Family::Imxrt1060
+            | Family::Imxrt1064 => 0x400F_8008,
+            Family::Imxrt1160 | Family::Imxrt1170 => 0x40C0_4010,
+            // SRC_GENERAL_REG.
+            Family::Imxrt1180 => 0x4446_0050,
+        }
+    }
+
+    /// Returns the FlexRAM bank allocations selectable through fuses.
+    ///
+    /// Each element pairs a value of the FlexRAM configuration fuse field
+    /// with the bank allocation it selects. Fuse values that aren't listed
+    /// are reserved. The all-zero fuse value always represents the
+    /// [default bank allocation](Self::default_flexram_banks). The table
+    /// is empty if the builder can't decode the chip's fuses.
+    fn flexram_fuse_table(self) -> &'static [(u32, FlexRamBanks)] {
+        const fn banks(ocram: usize, dtcm: usize, itcm: usize) -> FlexRamBanks {
+            FlexRamBanks { ocram, itcm, dtcm }
+        }
+        match self {
+            // FLEXRAM_CFG, two bits.
+            Family::Imxrt1010 | Family::Imxrt1015 => {
+                const {
+                    &[
+                        (0b00, banks(2, 1, 1)),
+                        (0b01, banks(3, 1, 0)),
+                        (0b10, banks(3, 0, 1)),
+                        (0b11, banks(4, 0, 0)),
+                    ]
+                }
+            }
+            // FLEXRAM_CFG, four bits.
+            Family::Imxrt1020 => {
+                const {
+                    &[
+                        (0b0000, banks(4, 2, 2)),
+                        (0b0001, banks(5, 2, 1)),
+                        (0b0010, banks(6, 1, 1)),
+                        (0b0011, banks(7, 1, 0)),
+                        (0b0100, banks(8, 0, 0)),
+                        (0b0101, banks(2, 2, 4)),
+                        (0b0110, banks(2, 4, 2)),
+                    ]
+                }
+            }
+            // FLEXRAM_CFG, four bits.
+            Family::Imxrt1040 | Family::Imxrt1050 | Family::Imxrt1060 | Family::Imxrt1064 => {
+                const {
+                    &[
+                        (0b0000, banks(8, 4, 4)),
+                        (0b0001, banks(10, 4, 2)),
+                        (0b0010, banks(12, 2, 2)),
+                        (0b0011, banks(14, 2, 0)),
+                        (0b0100, banks(16, 0, 0)),
+                        (0b0101, banks(4, 4, 8)),
+                        (0b0110, banks(4, 8, 4)),
+                    ]
+                }
+            }
+            // DEFAULT_FLEXRAM_PART, four bits. Assumes that ECC is disabled.
+            Family::Imxrt1160 | Family::Imxrt1170 => {
+                const {
+                    &[
+                        (0b0000, banks(0, 8, 8)),
+                        (0b0001, banks(0, 4, 12)),
+                        (0b0010, banks(0, 12, 4)),
+                        (0b0011, banks(0, 0, 16)),
+                        (0b0100, banks(0, 16, 0)),
+                    ]
+                }
+            }
+            // The builder doesn't know the 1180's fuse encoding.
+            Family::Imxrt1180 => &[],
+        }
+    }
 }
 
 /// If you're adding a new MCU family, you probably
@@ -1125,6 +3252,154 @@ impl Family {
     }
 }
 
+/// FlexRAM fuse values and configurations.
+///
+/// These methods translate between FlexRAM layouts, the chip's FlexRAM
+/// configuration fuses, and the `__flexram_config` value that the runtime
+/// writes into the FlexRAM configuration registers.
+impl Family {
+    /// Decode a FlexRAM configuration fuse value into bank allocations.
+    ///
+    /// `fuse_value` is the value of the chip's FlexRAM configuration fuse
+    /// field, right-aligned. Returns `None` if the fuse value is reserved
+    /// for this family, or if the builder can't decode the family's fuses.
+    /// The builder can't decode the 1180's fuses.
+    ///
+    /// The fuses only describe the number of banks for each memory kind.
+    /// The FlexRAM controller, not the runtime, decides the assignment of
+    /// individual banks.
+    ///
+    /// ```
+    /// use imxrt_rt::{Family, FlexRamBanks};
+    ///
+    /// let family = Family::Imxrt1060;
+    /// assert_eq!(
+    ///     family.flexram_banks_from_fuses(0),
+    ///     Some(family.default_flexram_banks())
+    /// );
+    /// assert_eq!(
+    ///     family.flexram_banks_from_fuses(0b0100),
+    ///     Some(FlexRamBanks { ocram: 16, dtcm: 0, itcm: 0 })
+    /// );
+    /// assert!(family.flexram_banks_from_fuses(0b1111).is_none());
+    /// ```
+    pub fn flexram_banks_from_fuses(self, fuse_value: u32) -> Option<FlexRamBanks> {
+        self.flexram_fuse_table()
+            .iter()
+            .find(|(value, _)| *value == fuse_value)
+            .map(|(_, banks)| *banks)
+    }
+
+    /// Compute the FlexRAM configuration fuse value for a layout.
+    ///
+    /// This is the inverse of [`flexram_banks_from_fuses`](Self::flexram_banks_from_fuses).
+    /// Use it to learn the fuse value you should burn so that the processor selects
+    /// `layout` out of reset. The fuses only describe the number of banks for each
+    /// memory kind, so the assignment of individual banks in `layout` doesn't matter.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the fuses cannot express the layout. This includes layouts
+    /// that leave banks unused, since all fuse configurations allocate every bank.
+    ///
+    /// ```
+    /// use imxrt_rt::{Family, FlexRamBanks};
+    ///
+    /// let family = Family::Imxrt1060;
+    /// let layout = family.default_flexram_layout();
+    /// assert_eq!(family.flexram_fuse_value(&layout).unwrap(), 0);
+    ///
+    /// let banks = FlexRamBanks { ocram: 16, dtcm: 0, itcm: 0 };
+    /// assert_eq!(banks.fuse_value(family).unwrap(), 0b0100);
+    ///
+    /// let banks = FlexRamBanks { ocram: 0, dtcm: 15, itcm: 1 };
+    /// assert!(banks.fuse_value(family).is_err());
+    /// ```
+    pub fn flexram_fuse_value(
+        self,
+        layout: &[FlexRamKind],
+    ) -> Result<u32, Box<dyn std::error::Error>> {
+        let banks = FlexRamBanks {
+            ocram: layout_count_of(FlexRamKind::Ocram, layout),
+            itcm: layout_count_of(FlexRamKind::Itcm, layout),
+            dtcm: layout_count_of(FlexRamKind::Dtcm, layout),
+        };
+        let unused = layout_count_of(FlexRamKind::Unused, layout);
+        self.flexram_fuse_table()
+            .iter()
+            .find(|(_, fused)| unused == 0 && *fused == banks)
+            .map(|(value, _)| *value)
+            .ok_or_else(|| {
+                format!("Chip {self:?} cannot fuse the FlexRAM layout {layout:?} ({banks:?})")
+                    .into()
+            })
+    }
+
+    /// Decode a FlexRAM configuration into a layout.
+    ///
+    /// `config` has the same representation as the `__flexram_config` value in the
+    /// generated linker script. For 10xx chips, `config` is the value of IOMUXC_GPR17.
+    /// For 11xx chips, the lower half-word is the value of IOMUXC_GPR17, and the upper
+    /// half-word is the value of IOMUXC_GPR18. For the 1180, `config` is the TCM_SIZE
+    /// field of the M33_CFG register in BLK_CTRL_S_AONMIX.
+    ///
+    /// The returned layout describes every FlexRAM bank on the chip. Banks that
+    /// aren't allocated are [`Unused`](FlexRamKind::Unused). Returns `None` if `config`
+    /// isn't a valid configuration for this family.
+    ///
+    /// ```
+    /// use imxrt_rt::{Family, FlexRamKind};
+    ///
+    /// let family = Family::Imxrt1010;
+    /// assert_eq!(
+    ///     family.flexram_layout_from_config(0b11_10_01_01),
+    ///     Some(family.default_flexram_layout())
+    /// );
+    /// assert_eq!(
+    ///     family.flexram_layout_from_config(0b00_00_10_11),
+    ///     Some(vec![
+    ///         FlexRamKind::Itcm,
+    ///         FlexRamKind::Dtcm,
+    ///         FlexRamKind::Unused,
+    ///         FlexRamKind::Unused,
+    ///     ])
+    /// );
+    /// ```
+    pub fn flexram_layout_from_config(self, config: u32) -> Option<Vec<FlexRamKind>> {
+        match self {
+            Family::Imxrt1180 => match config {
+                0b00 => Some(vec![FlexRamKind::Itcm, FlexRamKind::Dtcm]),
+                0b01 => Some(vec![FlexRamKind::Itcm, FlexRamKind::Itcm]),
+                0b10 => Some(vec![FlexRamKind::Dtcm, FlexRamKind::Dtcm]),
+                _ => None,
+            },
+            Family::Imxrt1010
+            | Family::Imxrt1015
+            | Family::Imxrt1020
+            | Family::Imxrt1040
+            | Family::Imxrt1050
+            | Family::Imxrt1060
+            | Family::Imxrt1064
+            | Family::Imxrt1160
+            | Family::Imxrt1170 => {
+                let bank_count = self.flexram_bank_count();
+                if bank_count < 16 && config >> (2 * bank_count) != 0 {
+                    return None;
+                }
+                let layout = (0..bank_count)
+                    .map(|bank| match (config >> (2 * bank)) & 0b11 {
+                        0b00 => FlexRamKind::Unused,
+                        0b01 => FlexRamKind::Ocram,
+                        0b10 => FlexRamKind::Dtcm,
+                        _ => FlexRamKind::Itcm,
+                    })
+                    .collect();
+                Some(layout)
+            }
+        }
+    }
+}
+
 /// FlexRAM bank allocations.
 ///
 /// Depending on your device, you may need a non-zero number of
@@ -1176,6 +3451,13 @@ impl FlexRamBanks {
         }
         layout
     }
+
+    /// Compute the FlexRAM configuration fuse value for these banks.
+    ///
+    /// See [`Family::flexram_fuse_value`] for more information.
+    pub fn fuse_value(self, family: Family) -> Result<u32, Box<dyn std::error::Error>> {
+        family.flexram_fuse_value(&self.to_flexram_layout())
+    }
 }
 
 /// Describes how a FlexRAM bank is being used.
@@ -1198,6 +3480,9 @@ pub enum FlexRamKind {
     Itcm = 3,
 }
 
+/// The CM7_CFGDTCMSZ and CM7_CFGITCMSZ fields in IOMUXC_GPR14.
+const TCM_SIZE_MASK: u32 = 0xFF << 16;
+
 /// Count how may RAM kinds there are in this layout.
 fn layout_count_of(kind: FlexRamKind, layout: &[FlexRamKind]) -> usize {
     layout.iter().filter(|k| **k == kind).count()
@@ -1217,8 +3502,8 @@ fn flexram_config(family: Family, layout: &[FlexRamKind]) -> u32 {
         let ocram_count = layout_count_of(FlexRamKind::Ocram, layout);
         match (itcm_count, dtcm_count, ocram_count) {
             (1, 1, 0) => 0b00_u32,
-            (2, 0, 0) => 0b10,
-            (0, 2, 0) => 0b01,
+            (2, 0, 0) => 0b01,
+            (0, 2, 0) => 0b10,
             _ => panic!("Unsupported FlexRAM configuration"),
         }
     } else {
@@ -1236,7 +3521,10 @@ fn flexram_config(family: Family, layout: &[FlexRamKind]) -> u32 {
 mod tests {
     use crate::Memory;
 
-    use super::{Family, FlexRamBanks, RuntimeBuilder};
+    use super::{
+        AhbRxBuffer, Family, FixedSectionKind, FlexRamBanks, FlexRamKind, FlexSpiAhb, Placement,
+        RuntimeBuilder, Section, Watchdog, WatchdogMode,
+    };
     use std::{error, io};
 
     const MOST_FAMILIES: &[Family] = &[
@@ -1392,8 +3680,10 @@ mod tests {
             placement!(vectors),
             placement!(bss),
             placement!(uninit),
+            placement!(retained),
             placement!(stack),
             placement!(heap),
+            placement!(relocated_vectors),
         ];
 
         for family in MOST_FAMILIES {
@@ -1432,6 +3722,486 @@ mod tests {
         }
     }
 
+    #[test]
+    fn flexram_from_fuses() -> Result<(), Error> {
+        for family in MOST_FAMILIES {
+            assert_eq!(
+                family.flexram_banks_from_fuses(0),
+                Some(family.default_flexram_banks()),
+                "{family:?}"
+            );
+            for (_, banks) in family.flexram_fuse_table() {
+                let total = banks.ocram + banks.dtcm + banks.itcm;
+                assert_eq!(total, family.flexram_bank_count(), "{family:?} {banks:?}");
+            }
+
+            let mut linker_script = Vec::new();
+            RuntimeBuilder::from_flexspi(*family, 16 * 1024 * 1024)
+                .flexram_from_fuses(0)
+                .write_linker_script(&mut linker_script)?;
+            let linker_script = String::from_utf8(linker_script)?;
+            assert!(linker_script.contains("__flexram_from_fuses = 0x00000001;"));
+
+            let res = RuntimeBuilder::from_flexspi(*family, 16 * 1024 * 1024)
+                .flexram_from_fuses(u32::MAX)
+                .write_linker_script(&mut io::sink());
+            assert!(res.is_err(), "{family:?}");
+        }
+
+        assert!(Family::Imxrt1180.flexram_banks_from_fuses(0).is_none());
+        let res = RuntimeBuilder::from_flexspi(Family::Imxrt1180, 16 * 1024 * 1024)
+            .flexram_from_fuses(0)
+            .write_linker_script(&mut io::sink());
+        assert!(res.is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn flexram_fuse_round_trip() {
+        for family in MOST_FAMILIES {
+            for (fuse_value, banks) in family.flexram_fuse_table() {
+                assert_eq!(
+                    banks.fuse_value(*family).unwrap(),
+                    *fuse_value,
+                    "{family:?} {banks:?}"
+                );
+            }
+            let mut layout = family.default_flexram_layout();
+            layout.pop();
+            assert!(family.flexram_fuse_value(&layout).is_err(), "{family:?}");
+            layout.push(FlexRamKind::Unused);
+            assert!(family.flexram_fuse_value(&layout).is_err(), "{family:?}");
+        }
+    }
+
+    #[test]
+    fn flexram_config_round_trip() {
+        for family in MOST_FAMILIES.iter().chain([&Family::Imxrt1180]) {
+            let layout = family.default_flexram_layout();
+            let config = super::flexram_config(*family, &layout);
+            assert_eq!(
+                family.flexram_layout_from_config(config),
+                Some(layout),
+                "{family:?}"
+            );
+        }
+        assert!(
+            Family::Imxrt1020
+                .flexram_layout_from_config(0x1_0000)
+                .is_none()
+        );
+        assert!(Family::Imxrt1180.flexram_layout_from_config(0b11).is_none());
+    }
+
+    #[test]
+    fn flexram_config_1180() {
+        // TCM_SIZE: 0b01 doubles the Code TCM (ITCM), and 0b10 doubles the
+        // System TCM (DTCM).
+        let family = Family::Imxrt1180;
+        for (layout, config) in [
+            ([FlexRamKind::Itcm, FlexRamKind::Itcm], 0b01),
+            ([FlexRamKind::Dtcm, FlexRamKind::Dtcm], 0b10),
+        ] {
+            assert_eq!(super::flexram_config(family, &layout), config);
+            assert_eq!(
+                family.flexram_layout_from_config(config),
+                Some(layout.to_vec())
+            );
+        }
+    }
+
+    #[test]
+    fn flexram_layout_overrides_fuses() -> Result<(), Error> {
+        let family = Family::Imxrt1060;
+        let mut linker_script = Vec::new();
+        RuntimeBuilder::from_flexspi(family, 16 * 1024 * 1024)
+            .flexram_from_fuses(u32::MAX)
+            .flexram_banks(family.default_flexram_banks())
+            .write_linker_script(&mut linker_script)?;
+        let linker_script = String::from_utf8(linker_script)?;
+        assert!(linker_script.contains("__flexram_from_fuses = 0x00000000;"));
+        Ok(())
+    }
+
+    #[test]
+    fn tcm_size_config() -> Result<(), Error> {
+        let family = Family::Imxrt1060;
+        // 128 KiB ITCM and DTCM.
+        assert_eq!(
+            family.tcm_size_config(&family.default_flexram_layout()),
+            Some(0x0088_0000)
+        );
+        // 416 KiB DTCM rounds up to 512 KiB. 96 KiB ITCM rounds up to 128 KiB.
+        let banks = FlexRamBanks {
+            ocram: 0,
+            dtcm: 13,
+            itcm: 3,
+        };
+        assert_eq!(
+            family.tcm_size_config(&banks.to_flexram_layout()),
+            Some(0x00A8_0000)
+        );
+        // No ITCM.
+        let banks = FlexRamBanks {
+            ocram: 15,
+            dtcm: 1,
+            itcm: 0,
+        };
+        assert_eq!(
+            family.tcm_size_config(&banks.to_flexram_layout()),
+            Some(0x0060_0000)
+        );
+
+        let mut linker_script = Vec::new();
+        RuntimeBuilder::from_flexspi(family, 16 * 1024 * 1024)
+            .restrict_tcm_sizes(true)
+            .write_linker_script(&mut linker_script)?;
+        let linker_script = String::from_utf8(linker_script)?;
+        assert!(linker_script.contains("__tcm_size_mask = 0x00FF0000;"));
+        assert!(linker_script.contains("__tcm_size_config = 0x00880000;"));
+
+        for family in [Family::Imxrt1170, Family::Imxrt1180] {
+            let res = RuntimeBuilder::from_flexspi(family, 16 * 1024 * 1024)
+                .restrict_tcm_sizes(true)
+                .write_linker_script(&mut io::sink());
+            assert!(res.is_err(), "{family:?}");
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn fixed_section() {
+        let family = Family::Imxrt1010;
+        // Outside of any memory region.
+        let res = RuntimeBuilder::from_flexspi(family, 16 * 1024 * 1024)
+            .fixed_section(".mailbox", 0x2021_0000, 4, FixedSectionKind::NoLoad)
+            .write_linker_script(&mut io::sink());
+        assert!(res.is_err());
+
+        // Straddles two memory regions.
+        let res = RuntimeBuilder::from_flexspi(family, 16 * 1024 * 1024)
+            .fixed_section(".mailbox", 0x2000_7FF0, 0x20, FixedSectionKind::NoLoad)
+            .write_linker_script(&mut io::sink());
+        assert!(res.is_err());
+
+        // Overlaps another fixed section.
+        let res = RuntimeBuilder::from_flexspi(family, 16 * 1024 * 1024)
+            .fixed_section(".mailbox", 0x2020_F000, 0x100, FixedSectionKind::NoLoad)
+            .fixed_section(".shared", 0x2020_F0F0, 0x100, FixedSectionKind::NoLoad)
+            .write_linker_script(&mut io::sink());
+        assert!(res.is_err());
+    }
+
+    #[test]
+    fn section_alignment() {
+        let family = Family::Imxrt1060;
+        let res = RuntimeBuilder::from_flexspi(family, 16 * 1024 * 1024)
+            .align(Section::Data, 24)
+            .write_linker_script(&mut io::sink());
+        assert!(res.is_err());
+
+        let res = RuntimeBuilder::from_flexspi(family, 16 * 1024 * 1024)
+            .align(Section::Custom(".unknown".into()), 32)
+            .write_linker_script(&mut io::sink());
+        assert!(res.is_err());
+
+        let res = RuntimeBuilder::from_flexspi(family, 16 * 1024 * 1024)
+            .fixed_section(".mailbox", 0x2020_0010, 32, FixedSectionKind::NoLoad)
+            .align(Section::Custom(".mailbox".into()), 32)
+            .write_linker_script(&mut io::sink());
+        assert!(res.is_err());
+    }
+
+    #[test]
+    fn defer() {
+        // FNV-1a of ".text".
+        assert_eq!(super::deferred_key(".text"), 0x88A8_2EC2);
+        for section in [
+            Section::Data,
+            Section::Bss,
+            Section::Stack,
+            Section::Custom(".unknown".into()),
+        ] {
+            let res = RuntimeBuilder::from_flexspi(Family::Imxrt1060, 16 * 1024 * 1024)
+                .defer(section.clone())
+                .write_linker_script(&mut io::sink());
+            assert!(res.is_err(), "{section:?}");
+        }
+    }
+
+    #[test]
+    fn compress() {
+        // Only the sections after read-only data, which stays in flash.
+        let mut builder = RuntimeBuilder::from_flexspi(Family::Imxrt1060, 1984 * 1024);
+        builder.rodata(Memory::Flash).compress(4 * 1024 * 1024);
+        assert_eq!(builder.compressed_sections(), [".data"]);
+
+        // No effect when executing from RAM.
+        let mut builder = RuntimeBuilder::from_ram(Family::Imxrt1060);
+        builder.compress(4 * 1024 * 1024);
+        assert!(builder.compressed_sections().is_empty());
+
+        let res = RuntimeBuilder::from_flexspi(Family::Imxrt1060, 1984 * 1024)
+            .compress(4 * 1024 * 1024)
+            .defer(Section::Text)
+            .write_linker_script(&mut io::sink());
+        assert!(res.is_err());
+        let res = RuntimeBuilder::from_flexspi(Family::Imxrt1180, 16 * 1024 * 1024)
+            .compress(32 * 1024 * 1024)
+            .write_linker_script(&mut io::sink());
+        assert!(res.is_err());
+    }
+
+    #[test]
+    fn core_clock() {
+        let clock = Family::Imxrt1060.core_clock_config(600).unwrap();
+        assert_eq!((clock.div_select, clock.arm_podf), (100, 1));
+        assert_eq!((clock.ipg_podf, clock.dcdc_trg), (3, 0x12));
+        let clock = Family::Imxrt1050.core_clock_config(528).unwrap();
+        assert_eq!((clock.div_select, clock.arm_podf), (88, 1));
+        assert_eq!((clock.ipg_podf, clock.dcdc_trg), (3, 0x0E));
+        let clock = Family::Imxrt1060.core_clock_config(132).unwrap();
+        assert_eq!(
+            (clock.div_select, clock.arm_podf, clock.ipg_podf),
+            (55, 4, 0)
+        );
+        let clock = Family::Imxrt1160.core_clock_config(600).unwrap();
+        assert_eq!((clock.div_select, clock.arm_podf), (200, 1));
+        assert_eq!((clock.ipg_podf, clock.dcdc_trg), (0, 0x10));
+        let clock = Family::Imxrt1170.core_clock_config(240).unwrap();
+        assert_eq!((clock.div_select, clock.arm_podf), (160, 2));
+
+        let mut linker_script = Vec::new();
+        RuntimeBuilder::from_flexspi(Family::Imxrt1170, 16 * 1024 * 1024)
+            .core_clock_mhz(996)
+            .write_linker_script(&mut linker_script)
+            .unwrap();
+        let linker_script = String::from_utf8(linker_script).unwrap();
+        for symbol in [
+            "__arm_pll_div_select = 0x000000A6;",
+            "__arm_podf = 0x00000000;",
+            "__ipg_podf = 0x00000000;",
+            "__dcdc_trg = 0x00000015;",
+        ] {
+            assert!(linker_script.contains(symbol), "{symbol}");
+        }
+
+        for (family, mhz) in [
+            (Family::Imxrt1060, 601),
+            (Family::Imxrt1060, 80),
+            (Family::Imxrt1060, 599),
+            (Family::Imxrt1010, 500),
+            (Family::Imxrt1170, 1002),
+            (Family::Imxrt1170, 155),
+            (Family::Imxrt1160, 996),
+        ] {
+            let result = RuntimeBuilder::from_flexspi(family, 16 * 1024 * 1024)
+                .core_clock_mhz(mhz)
+                .write_linker_script(&mut Vec::new());
+            assert!(result.is_err(), "{family:?} {mhz}MHz");
+        }
+    }
+
+    #[test]
+    fn flexspi_ahb() -> Result<(), Error> {
+        let ahb = FlexSpiAhb {
+            prefetch: true,
+            buffers: vec![
+                AhbRxBuffer {
+                    master_id: 0,
+                    size: 768,
+                    prefetch: true,
+                },
+                AhbRxBuffer {
+                    master_id: 3,
+                    size: 256,
+                    prefetch: false,
+                },
+            ],
+        };
+        assert_eq!(
+            ahb.buffer_config().collect::<Vec<_>>(),
+            [0x8000_0060, 0x0003_0020]
+        );
+
+        let buffer = AhbRxBuffer {
+            master_id: 0,
+            size: 256,
+            prefetch: true,
+        };
+        for (family, buffers) in [
+            (Family::Imxrt1060, vec![buffer; 5]),
+            (
+                Family::Imxrt1060,
+                vec![AhbRxBuffer {
+                    size: 260,
+                    ..buffer
+                }],
+            ),
+            (
+                Family::Imxrt1060,
+                vec![AhbRxBuffer {
+                    size: 2048,
+                    ..buffer
+                }],
+            ),
+            (
+                Family::Imxrt1060,
+                vec![AhbRxBuffer {
+                    master_id: 16,
+                    ..buffer
+                }],
+            ),
+            (Family::Imxrt1170, vec![buffer; 9]),
+            (Family::Imxrt1180, vec![buffer]),
+        ] {
+            let result = RuntimeBuilder::from_flexspi(family, 16 * 1024 * 1024)
+                .flexspi_ahb(FlexSpiAhb {
+                    prefetch: true,
+                    buffers,
+                })
+                .write_linker_script(&mut Vec::new());
+            assert!(result.is_err(), "{family:?}");
+        }
+
+        // 5 * 256 bytes exceeds the 1KiB total on the 1060, but not on the 1170.
+        RuntimeBuilder::from_flexspi(Family::Imxrt1170, 16 * 1024 * 1024)
+            .flexspi_ahb(FlexSpiAhb {
+                prefetch: true,
+                buffers: vec![buffer; 5],
+            })
+            .write_linker_script(&mut Vec::new())?;
+        Ok(())
+    }
+
+    #[test]
+    fn watchdogs() -> Result<(), String> {
+        let builder = RuntimeBuilder::from_flexspi(Family::Imxrt1060, 16 * 1024 * 1024);
+        assert_eq!(builder.watchdog_mode(Watchdog::Wdog1), WatchdogMode::Keep);
+        assert_eq!(builder.watchdog_mode(Watchdog::Wdog2), WatchdogMode::Keep);
+        assert_eq!(
+            builder.watchdog_mode(Watchdog::Rtwdog),
+            WatchdogMode::Disable
+        );
+        let mut builder = RuntimeBuilder::from_flexspi(Family::Imxrt1180, 16 * 1024 * 1024);
+        assert_eq!(builder.watchdog_mode(Watchdog::Rtwdog), WatchdogMode::Keep);
+        assert_eq!(builder.wdog_config(Watchdog::Wdog1)?, (1, 0));
+        builder.watchdog(Watchdog::Wdog2, WatchdogMode::Enable { timeout_ms: 1000 });
+        assert_eq!(builder.wdog_config(Watchdog::Wdog2)?, (0x21A0, 0x8000));
+
+        assert_eq!(
+            WatchdogMode::Enable { timeout_ms: 2000 }.wdog_config()?,
+            0x334
+        );
+        assert_eq!(WatchdogMode::Disable.wdog_config()?, 0x1);
+        assert_eq!(
+            WatchdogMode::Enable { timeout_ms: 1000 }.rtwdog_config()?,
+            (0x21A0, 0x8000)
+        );
+        // Too many ticks for the LPO clock, so the RTWDOG uses the prescaler.
+        assert_eq!(
+            WatchdogMode::Enable { timeout_ms: 10_000 }.rtwdog_config()?,
+            (0x31A0, 0x500)
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn watchdogs_invalid() {
+        for (family, watchdog, mode) in [
+            (
+                Family::Imxrt1060,
+                Watchdog::Wdog1,
+                WatchdogMode::Enable { timeout_ms: 400 },
+            ),
+            (
+                Family::Imxrt1060,
+                Watchdog::Wdog2,
+                WatchdogMode::Enable {
+                    timeout_ms: 128_001,
+                },
+            ),
+            (
+                Family::Imxrt1060,
+                Watchdog::Rtwdog,
+                WatchdogMode::Enable { timeout_ms: 0 },
+            ),
+            (
+                Family::Imxrt1060,
+                Watchdog::Rtwdog,
+                WatchdogMode::Enable {
+                    timeout_ms: 600_000,
+                },
+            ),
+            (Family::Imxrt1180, Watchdog::Rtwdog, WatchdogMode::Disable),
+            (
+                Family::Imxrt1180,
+                Watchdog::Wdog1,
+                WatchdogMode::Enable {
+                    timeout_ms: 600_000,
+                },
+            ),
+        ] {
+            let mut linker_script = Vec::new();
+            let result = RuntimeBuilder::from_flexspi(family, 16 * 1024 * 1024)
+                .watchdog(watchdog, mode)
+                .write_linker_script(&mut linker_script);
+            assert!(result.is_err(), "{family:?} {watchdog:?} {mode:?}");
+        }
+    }
+
+    #[test]
+    fn interrupts() {
+        for (count, align) in [(0, 0x400), (240, 0x400), (241, 0x800), (496, 0x800)] {
+            assert_eq!(super::vector_table_align(count), align, "{count}");
+        }
+
+        for (family, count) in [(Family::Imxrt1060, 497), (Family::Imxrt1180, 481)] {
+            let mut linker_script = Vec::new();
+            let result = RuntimeBuilder::from_flexspi(family, 16 * 1024 * 1024)
+                .interrupts(count)
+                .write_linker_script(&mut linker_script);
+            assert!(result.is_err(), "{family:?} {count}");
+        }
+    }
+
+    #[test]
+    fn placements() {
+        // ITCM text, OCRAM rodata, data, bss, and uninit, and DTCM for the rest.
+        let builder = RuntimeBuilder::from_flexspi(Family::Imxrt1060, 16 * 1024 * 1024);
+        assert_eq!(builder.placements(), 0x000A_BEF5);
+
+        let mut builder = RuntimeBuilder::from_flexspi(Family::Imxrt1060, 16 * 1024 * 1024);
+        builder
+            .text(Placement::Prefer(Memory::Itcm).fallback(Memory::Flash))
+            .rodata(Memory::Flash)
+            .heap(Memory::Ocram);
+        assert_eq!(builder.placements(), 0x000E_BEC1);
+    }
+
+    #[test]
+    fn reset_reason() -> Result<(), Error> {
+        let mut linker_script = Vec::new();
+        RuntimeBuilder::from_flexspi(Family::Imxrt1180, 16 * 1024 * 1024)
+            .reset_reason(true)
+            .write_linker_script(&mut linker_script)?;
+        let linker_script = String::from_utf8(linker_script)?;
+        assert!(linker_script.contains("__reset_reason_size = 0x4;"));
+        assert!(linker_script.contains("__src_srsr = 0x44460050;"));
+        Ok(())
+    }
+
+    #[test]
+    fn init_arrays() {
+        for section in [Section::Text, Section::Rodata] {
+            let result = RuntimeBuilder::from_flexspi(Family::Imxrt1060, 16 * 1024 * 1024)
+                .init_arrays(true)
+                .defer(section.clone())
+                .write_linker_script(&mut Vec::new());
+            assert!(result.is_err(), "{section:?}");
+        }
+    }
+
     #[test]
     fn default_flexram_layouts() {
         let cases = [
@@ -0,0 +1,314 @@
+//! Compressing a linked program.
+//!
+//! The runtime builder adds a compression table to the program. This module
+//! finds that table in the program's flash image, compresses the sections
+//! described by the table, and updates the table. The target runtime uses
+//! the table to decompress the sections.
+
+use std::error::Error;
+
+use super::image::{find_table, read_u32, write_u32};
+
+/// Marks the start of the compression table.
+pub(crate) const COMPRESSION_MAGIC: u32 = 0x5A4C_5249;
+
+/// The table's sections are stored uncompressed.
+const STATE_STORED: u32 = 0;
+/// The table's sections are compressed with LZ4.
+pub(super) const STATE_LZ4: u32 = 1;
+
+/// Size of the table header, in bytes.
+const HEADER_SIZE: usize = 20;
+/// Size of a table entry, in bytes.
+const ENTRY_SIZE: usize = 16;
+
+/// An entry in the compression table.
+#[derive(Debug, Clone, Copy)]
+struct Entry {
+    /// Offset of the entry in the image.
+    offset: usize,
+    /// Size of the section, in bytes.
+    size: usize,
+    /// Offset of the section's load image.
+    load: usize,
+}
+
+/// Compress the program in `image`.
+///
+/// `image` is the flash contents of a program linked with a
+/// [compressing runtime](crate::RuntimeBuilder::compress). It starts at the
+/// program's first flash address, like the output of `objcopy -O binary`.
+/// The return is the compressed flash contents. Program the return into
+/// flash, instead of the original image.
+///
+/// The sections are compressed with LZ4. The return is shorter than `image`,
+/// unless the sections don't compress well.
+///
+/// # Errors
+///
+/// Returns an error if the image doesn't have a compression table, or if the
+/// image is already compressed. Returns an error if there's content after the
+/// compressed sections, or if the compressed image doesn't fit in flash.
+pub fn compress_image(image: &[u8]) -> Result<Vec<u8>, Box<dyn Error>> {
+    let (table, base) =
+        find_table(image, COMPRESSION_MAGIC).ok_or("Image has no compression table")?;
+    let header = |idx: usize| read_u32(image, table + idx * 4).ok_or("Truncated table header");
+    match header(2)? {
+        STATE_STORED => {}
+        STATE_LZ4 => return Err("Image is already compressed".into()),
+        state => return Err(format!("Unknown compression state {state}").into()),
+    }
+    let flash_end = header(3)?
+        .checked_sub(base)
+        .ok_or("Flash ends before the image")? as usize;
+
+    let count = header(4)? as usize;
+
+    let mut entries: Vec<Entry> = Vec::new();
+    for idx in 0..count {
+        let offset = table + HEADER_SIZE + idx * ENTRY_SIZE;
+        let field = |idx: usize| read_u32(image, offset + idx * 4).ok_or("Truncated table entry");
+        let size = field(3)? as usize;
+        // The linker may not give an empty section a meaningful load address.
+        if size == 0 {
+            continue;
+        }
+        let load = field(2)?
+            .checked_sub(base)
+            .map(|load| load as usize)
+            .filter(|load| table < *load && load + size <= image.len())
+            .ok_or("Section's load image is outside of the image")?;
+        if entries
+            .last()
+            .is_some_and(|prev| load < prev.load + prev.size)
+        {
+            return Err("Sections' load images are out of order".into());
+        }
+        entries.push(Entry { offset, size, load });
+    }
+    let Some(first) = entries.first() else {
+        return Err("Compression table has no sections".into());
+    };
+    let last = entries.last().unwrap();
+    if last.load + last.size < image.len() {
+        return Err("Image has content after the compressed sections".into());
+    }
+
+    let mut compressed = image[..first.load].to_vec();
+    for entry in &entries {
+        let block = lz4_compress(&image[entry.load..entry.load + entry.size]);
+        let load = compressed.len();
+        compressed.extend_from_slice(&block);
+        compressed.resize(compressed.len().next_multiple_of(4), 0xFF);
+        write_u32(&mut compressed, entry.offset + 8, base + load as u32);
+        write_u32(&mut compressed, entry.offset + 12, block.len() as u32);
+    }
+    write_u32(&mut compressed, table + 8, STATE_LZ4);
+
+    if compressed.len() > flash_end {
+        return Err(format!(
+            "Compressed image is {} bytes, but flash can only hold {flash_end} bytes",
+            compressed.len()
+        )
+        .into());
+    }
+    Ok(compressed)
+}
+
+/// The smallest match encoded by LZ4.
+const MIN_MATCH: usize = 4;
+/// The largest offset encoded by LZ4.
+const MAX_OFFSET: usize = u16::MAX as usize;
+
+/// Compress `input` into an LZ4 block.
+///
+/// This is a simple, greedy compressor. It favors simplicity over the compression
+/// ratio. An empty input produces an empty block.
+fn lz4_compress(input: &[u8]) -> Vec<u8> {
+    const HASH_BITS: u32 = 12;
+    let mut output = Vec::new();
+    if input.is_empty() {
+        return output;
+    }
+
+    let mut table = vec![usize::MAX; 1 << HASH_BITS];
+    let mut anchor = 0;
+    let mut pos = 0;
+    while pos + MIN_MATCH <= input.len() {
+        let sequence = u32::from_le_bytes(input[pos..pos + MIN_MATCH].try_into().unwrap());
+        let hash = (sequence.wrapping_mul(2_654_435_761) >> (32 - HASH_BITS)) as usize;
+        let candidate = std::mem::replace(&mut table[hash], pos);
+        if candidate == usize::MAX
+            || pos - candidate > MAX_OFFSET
+            || input[candidate..candidate + MIN_MATCH] != input[pos..pos + MIN_MATCH]
+        {
+            pos += 1;
+            continue;
+        }
+        let len = MIN_MATCH
+            + input[pos + MIN_MATCH..]
+                .iter()
+                .zip(&input[candidate + MIN_MATCH..])
+                .take_while(|(a, b)| a == b)
+                .count();
+        write_sequence(
+            &mut output,
+            &input[anchor..pos],
+            Some((pos - candidate, len)),
+        );
+        pos += len;
+        anchor = pos;
+    }
+    write_sequence(&mut output, &input[anchor..], None);
+    output
+}
+
+/// Write a sequence of literals, followed by an optional match.
+///
+/// The match is its offset and length.
+fn write_sequence(output: &mut Vec<u8>, literals: &[u8], matched: Option<(usize, usize)>) {
+    fn write_length(output: &mut Vec<u8>, mut len: usize) {
+        while len >= 255 {
+            output.push(255);
+            len -= 255;
+        }
+        output.push(len as u8);
+    }
+
+    let match_len = matched.map_or(0, |(_, len)| len - MIN_MATCH);
+    output.push(((literals.len().min(15) as u8) << 4) | match_len.min(15) as u8);
+    if literals.len() >= 15 {
+        write_length(output, literals.len() - 15);
+    }
+    output.extend_from_slice(literals);
+    if let Some((offset, _)) = matched {
+        output.extend_from_slice(&(offset as u16).to_le_bytes());
+        if match_len >= 15 {
+            write_length(output, match_len - 15);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{COMPRESSION_MAGIC, compress_image, lz4_compress};
+
+    /// Mirrors the target runtime's decompressor.
+    fn lz4_decompress(mut input: &[u8]) -> Vec<u8> {
+        fn read_length(input: &mut &[u8], mut len: usize) -> usize {
+            if len == 15 {
+                loop {
+                    let byte = input[0];
+                    *input = &input[1..];
+                    len += byte as usize;
+                    if byte != 255 {
+                        break;
+                    }
+                }
+            }
+            len
+        }
+
+        let mut output = Vec::new();
+        while let Some((&token, rest)) = input.split_first() {
+            input = rest;
+            let literals = read_length(&mut input, (token >> 4) as usize);
+            output.extend_from_slice(&input[..literals]);
+            input = &input[literals..];
+            if input.is_empty() {
+                break;
+            }
+            let offset = u16::from_le_bytes([input[0], input[1]]) as usize;
+            input = &input[2..];
+            let len = read_length(&mut input, (token & 0xF) as usize) + 4;
+            for _ in 0..len {
+                output.push(output[output.len() - offset]);
+            }
+        }
+        output
+    }
+
+    #[test]
+    fn lz4_round_trip() {
+        let repetitive: Vec<u8> = (0..10_000u32).map(|x| (x % 7) as u8).collect();
+        let noisy: Vec<u8> = (0..10_000u32)
+            .map(|x| (x.wrapping_mul(2_654_435_761) >> 13) as u8)
+            .collect();
+        let runs: Vec<u8> = [0xFFu8; 1000].into_iter().chain([0u8; 300]).collect();
+        for input in [
+            &[][..],
+            b"abc",
+            b"abcdabcdabcdabcd",
+            &repetitive,
+            &noisy,
+            &runs,
+        ] {
+            let compressed = lz4_compress(input);
+            assert_eq!(lz4_decompress(&compressed), input);
+        }
+        assert!(lz4_compress(&repetitive).len() < repetitive.len() / 10);
+    }
+
+    /// Produce an image at 0x6000_0000 with two sections in the compression table.
+    fn test_image(flash_size: u32) -> (Vec<u8>, Vec<u8>, Vec<u8>) {
+        let text: Vec<u8> = (0..4096u32).map(|x| (x % 13) as u8).collect();
+        let data: Vec<u8> = (0..256u32).map(|x| x as u8).collect();
+        let mut image = vec![0xFFu8; 64];
+        for word in [
+            COMPRESSION_MAGIC,
+            0x6000_0040,
+            0,
+            0x6000_0000 + flash_size,
+            2,
+            0x0000_0020,
+            0x0000_0020 + text.len() as u32,
+            0x6000_0080,
+            text.len() as u32,
+            0x2020_0000,
+            0x2020_0000 + data.len() as u32,
+            0x6000_0080 + text.len() as u32,
+            data.len() as u32,
+        ] {
+            image.extend_from_slice(&word.to_le_bytes());
+        }
+        image.resize(0x80, 0xFF);
+        image.extend_from_slice(&text);
+        image.extend_from_slice(&data);
+        (image, text, data)
+    }
+
+    #[test]
+    fn compress_image_table() {
+        let (image, text, data) = test_image(16 * 1024);
+        let compressed = compress_image(&image).unwrap();
+        assert!(compressed.len() < image.len());
+        assert_eq!(compressed[..0x40], image[..0x40]);
+
+        let word = |offset: usize| {
+            u32::from_le_bytes(compressed[offset..offset + 4].try_into().unwrap()) as usize
+        };
+        assert_eq!(word(0x48), 1, "compressed with LZ4");
+        for (entry, expected) in [(0x54, &text), (0x64, &data)] {
+            let load = word(entry + 8) - 0x6000_0000;
+            let size = word(entry + 12);
+            assert_eq!(&lz4_decompress(&compressed[load..load + size]), expected);
+        }
+
+        assert!(compress_image(&compressed).is_err(), "already compressed");
+    }
+
+    #[test]
+    fn compress_image_errors() {
+        let (image, _, _) = test_image(128);
+        assert!(compress_image(&image).is_err(), "doesn't fit in flash");
+
+        let (mut image, _, _) = test_image(16 * 1024);
+        image.push(0);
+        assert!(
+            compress_image(&image).is_err(),
+            "content after the sections"
+        );
+
+        assert!(compress_image(&[0; 256]).is_err(), "no table");
+    }
+}
@@ -0,0 +1,26 @@
+//! Helpers for post-link steps that modify a program's flash image.
+
+/// Read the little-endian word at `offset`.
+pub(super) fn read_u32(image: &[u8], offset: usize) -> Option<u32> {
+    let bytes = image.get(offset..offset + 4)?;
+    Some(u32::from_le_bytes(bytes.try_into().unwrap()))
+}
+
+/// Write the little-endian word at `offset`.
+pub(super) fn write_u32(image: &mut [u8], offset: usize, value: u32) {
+    image[offset..offset + 4].copy_from_slice(&value.to_le_bytes());
+}
+
+/// Find a table that starts with `magic`, followed by the table's address.
+///
+/// Returns the offset of the table, and the image's base address.
+pub(super) fn find_table(image: &[u8], magic: u32) -> Option<(usize, u32)> {
+    (0..image.len()).step_by(4).find_map(|offset| {
+        if read_u32(image, offset)? != magic {
+            return None;
+        }
+        let address = read_u32(image, offset + 4)?;
+        let base = address.checked_sub(offset as u32)?;
+        Some((offset, base))
+    })
+}
@@ -3,7 +3,9 @@
  * runtime configuration.
  */
 
-__image_size = SIZEOF(.vector_table) + SIZEOF(.text) + SIZEOF(.xip) + SIZEOF(.rodata) + SIZEOF(.data);
+/* Spans the contiguous sections starting from the vector table, including
+ * any alignment padding between sections. */
+__image_size = LOADADDR(.data) + SIZEOF(.data) - LOADADDR(.vector_table);
 
 EXTERN(FLEXSPI_CONFIGURATION_BLOCK);
 
@@ -6,9 +6,10 @@
 /* If you're ever playing with the boot ROM copy, this is your image size.
  *
  * Note that it depends on the section layout! Need to represent contiguous
- * sections starting from the boot header.
+ * sections starting from the boot header. The span includes any alignment
+ * padding between sections.
  */
-__image_size = SIZEOF(.boot) + SIZEOF(.vector_table) + SIZEOF(.xip) + SIZEOF(.text) + SIZEOF(.rodata);
+__image_size = LOADADDR(.rodata) + SIZEOF(.rodata) - ORIGIN(FLASH);
 
 /* END TODO */
 EXTERN(FLEXSPI_CONFIGURATION_BLOCK);
@@ -29,7 +29,9 @@ PROVIDE(PendSV = DefaultHandler);
 PROVIDE(SysTick = DefaultHandler);
 
 PROVIDE(DefaultHandler = DefaultHandler_);
-PROVIDE(HardFault = HardFault_);
+PROVIDE(IntegrityCheckFailed = __imxrt_rt_integrity_check_failed);
+PROVIDE(__imxrt_user_pre_init = __imxrt_rt_user_pre_init);
+PROVIDE(HardFault = __imxrt_rt_hard_fault);
 
 /* # Interrupt vectors */
 EXTERN(__INTERRUPTS); /* `static` variable similar to `__EXCEPTIONS` */
@@ -37,17 +39,17 @@ EXTERN(__INTERRUPTS); /* `static` variable similar to `__EXCEPTIONS` */
 /* # Sections */
 SECTIONS
 {
-  .stack (NOLOAD) : ALIGN(8)
+  .stack (NOLOAD) : ALIGN(__stack_align)
   {
     __estack = .;
-    . += ALIGN(__stack_size, 8);
+    . += ALIGN(__stack_size, __stack_align);
     __sstack = .;
     /* Symbols expected by cortex-m-rt */
     _stack_start = __sstack;
     _stack_end   = __estack;
   } > REGION_STACK
 
-  .vector_table : ALIGN(1024)
+  .vector_table : ALIGN(__vector_table_align)
   {
     FILL(0xff);
     __vector_table = .;
@@ -70,6 +72,27 @@ SECTIONS
   } > REGION_VTABLE AT> REGION_LOAD_VTABLE
   __sivector_table = LOADADDR(.vector_table);
 
+  /* Interrupt entries reserved by the runtime builder, beyond the device's interrupts.
+     Not in the image; the runtime points them at DefaultHandler. */
+  .vector_table_reserved (NOLOAD) : ALIGN(4)
+  {
+    __svector_table_reserved = .;
+    . = MAX(., __svector_table + (16 + __interrupt_count) * 4);
+    __evector_table_reserved = .;
+  } > REGION_VTABLE
+
+  /* Never initialized by the runtime, so the contents can survive a warm reset.
+     Placed early, so that its address is stable. */
+  .retained (NOLOAD) : ALIGN(4)
+  {
+    __crash_record = .; /* Written by the runtime's HardFault handler, if enabled. */
+    . += __crash_record_size;
+    __sretained = .;
+    *(.retained .retained.*);
+    . = MAX(., __sretained + __retained_size);
+    __eretained = .;
+  } > REGION_RETAINED
+
   /* This section guarantees VMA = LMA to allow the execute-in-place entry point to be inside the image. */
   .xip : ALIGN(4)
   {
@@ -77,74 +100,145 @@ SECTIONS
     *(.Reset);
     *(.__pre_init);
     *(.xip .xip.*);
+
+    /* Describes the additional output sections that the runtime copies. The
+       runtime builder generates these entries. */
+    . = ALIGN(4);
+    __scopy_table = .;
+    /* imxrt-rt: generated copy table */
+    __ecopy_table = .;
+
+    /* Describes the sections that the runtime copies when the user asks. Each
+       entry starts with a key that identifies the section. */
+    __sdeferred_table = .;
+    /* imxrt-rt: generated deferred table */
+    __edeferred_table = .;
+
+    /* Describes the sections that the runtime decompresses. The post-link
+       step compresses the sections, and updates this table. */
+    __scompression_table = .;
+    /* imxrt-rt: generated compression table */
+    __ecompression_table = .;
+
+    /* Describes the sections that the runtime checks after it copies them. The
+       post-link step computes the CRCs, and updates this table. */
+    __sintegrity_table = .;
+    /* imxrt-rt: generated integrity table */
+    __eintegrity_table = .;
+    __integrity_crc_table = .;
+    /* imxrt-rt: generated CRC table */
+
+    /* Static constructors. The runtime calls these if the runtime builder asks. */
+    . = ALIGN(4);
+    __preinit_array_start = .;
+    KEEP(*(.preinit_array .preinit_array.*));
+    __preinit_array_end = .;
+    __init_array_start = .;
+    KEEP(*(SORT_BY_INIT_PRIORITY(.init_array.*)));
+    KEEP(*(.init_array));
+    __init_array_end = .;
   } > REGION_LOAD_TEXT
 
-  .text : ALIGN(4)
+  /* Output sections generated by the runtime builder. These take input sections
+     before the catch-all rules in the output sections below. */
+  /* imxrt-rt: generated output sections */
+
+  .text : ALIGN(__text_align)
   {
     FILL(0xff);
     __stext = .;
-    *(.text .text.*);
     /* The HardFaultTrampoline uses the `b` instruction to enter `HardFault`,
-       so must be placed close to it. */
+       so must be placed close to it. These never spill. */
     *(.HardFaultTrampoline);
     *(.HardFault.*);
-    . = ALIGN(4); /* Pad .text to the alignment to workaround overlapping load section bug in old lld */
+    /* Hot code is placed first, so it's the last to spill. */
+    *(.text.hot .text.hot.*);
+    *(.text .text.*);
+    . = ALIGN(__text_align); /* Pad .text to the alignment to workaround overlapping load section bug in old lld */
     __etext = .;
   } > REGION_TEXT AT> REGION_LOAD_TEXT
   __sitext = LOADADDR(.text);
 
-  .rodata : ALIGN(4)
+  /* Takes the .text input sections that don't fit in REGION_TEXT. This is only
+     populated when the linker supports non-contiguous regions. Otherwise, it's empty. */
+  .text_spill : ALIGN(4)
+  {
+    FILL(0xff);
+    __stext_spill = .;
+    *(.text.hot .text.hot.*);
+    *(.text .text.*);
+    . = ALIGN(4);
+    __etext_spill = .;
+  } > REGION_TEXT_SPILL AT> REGION_LOAD_TEXT_SPILL
+  __sitext_spill = LOADADDR(.text_spill);
+
+  .rodata : ALIGN(__rodata_align)
   {
     FILL(0xff);
     . = ALIGN(4);
     __srodata = .;
     *(.rodata .rodata.*);
 
-    /* 4-byte align the end (VMA) of this section.
+    /* Align the end (VMA) of this section, at least to 4 bytes.
        This is required by LLD to ensure the LMA of the following .data
        section will have the correct alignment. */
-    . = ALIGN(4);
+    . = ALIGN(__rodata_align);
     __erodata = .;
   } > REGION_RODATA AT> REGION_LOAD_RODATA
   __sirodata = LOADADDR(.rodata);
 
-  .data : ALIGN(4)
+  .data : ALIGN(__data_align)
   {
     FILL(0xff);
     . = ALIGN(4);
     __sdata = .;
     *(.data .data.*);
-    . = ALIGN(4); /* 4-byte align the end (VMA) of this section */
+    . = ALIGN(__data_align); /* Align the end (VMA) of this section */
     __edata = .;
   } > REGION_DATA AT> REGION_LOAD_DATA
-  __sidata = LOADADDR(.data);
+  /* If compressed, the runtime decompresses .data. If the runtime initializes statics,
+     for static constructors or boot timings, it initializes .data itself. Either way,
+     cortex-m-rt copies .data onto itself. */
+  __sidata_runtime = __data_compressed ? __sdata : LOADADDR(.data);
+  __sidata = __init_statics ? __sdata : __sidata_runtime;
 
   _ram_start = __sdata;
   _ram_end = __edata;
 
-  .bss (NOLOAD) : ALIGN(4)
+  .bss (NOLOAD) : ALIGN(__bss_align)
   {
     . = ALIGN(4);
     __sbss = .;
     *(.bss .bss.*);
     *(COMMON); /* Uninitialized C statics */
-    . = ALIGN(4); /* 4-byte align the end (VMA) of this section */
-      __ebss = .;
+    . = ALIGN(__bss_align); /* Align the end (VMA) of this section */
+      __ebss_runtime = .;
   } > REGION_BSS
+  /* If the runtime initializes statics, it zeroes .bss itself. Then, cortex-m-rt
+     zeroes nothing. */
+  __ebss = __init_statics ? __sbss : __ebss_runtime;
 
-  .uninit (NOLOAD) : ALIGN(4)
+  .uninit (NOLOAD) : ALIGN(__uninit_align)
   {
     . = ALIGN(4);
     __suninit = .;
     *(.uninit .uninit.*);
     . = ALIGN(4);
+    __boot_timings = .; /* Written by the runtime, if enabled. */
+    . += __boot_timings_size;
+    __reset_reason = .; /* Written by the runtime, if enabled. */
+    . += __reset_reason_size;
+    . = ALIGN(__crash_stack_size ? 8 : 1);
+    . += __crash_stack_size;
+    __crash_stack = .; /* Top of the crash record handler's stack, if enabled. */
+    . = ALIGN(__uninit_align);
     __euninit = .;
   } > REGION_UNINIT
 
-  .heap (NOLOAD) : ALIGN(4)
+  .heap (NOLOAD) : ALIGN(__heap_align)
   {
     __sheap = .;
-    . += ALIGN(__heap_size, 4);
+    . += ALIGN(__heap_size, __heap_align);
     __eheap = .;
   } > REGION_HEAP
 
@@ -155,13 +249,8 @@ SECTIONS
     KEEP(*(.got .got.*));
   }
 
-  /DISCARD/ :
-  {
-    /* Unused exception related info that only wastes space */
-    *(.ARM.exidx);
-    *(.ARM.exidx.*);
-    *(.ARM.extab.*);
-  }
+  /* Unless the runtime builder keeps the exception unwinding tables, it discards
+     them with the generated output sections. */
 }
 
 /* Do not exceed this mark in the error messages below                                    | */
@@ -173,10 +262,10 @@ BUG(imxrt-rt): .stack is not 8-byte aligned");
 ASSERT(__sdata % 4 == 0 && __edata % 4 == 0, "
 BUG(imxrt-rt): .data is not 4-byte aligned");
 
-ASSERT(__sidata % 4 == 0, "
+ASSERT(__sidata_runtime % 4 == 0, "
 BUG(imxrt-rt): the LMA of .data is not 4-byte aligned");
 
-ASSERT(__sbss % 4 == 0 && __ebss % 4 == 0, "
+ASSERT(__sbss % 4 == 0 && __ebss_runtime % 4 == 0, "
 BUG(imxrt-rt): .bss is not 4-byte aligned");
 
 ASSERT(__sheap % 4 == 0, "
@@ -201,6 +290,9 @@ may be enabling it)
 - Supply the interrupt handlers yourself. Check the documentation for details.");
 
 /* # Other checks */
+ASSERT(__retained_size == 0 || __eretained == __sretained + __retained_size, "
+ERROR(imxrt-rt): .retained exceeds the size reserved by the runtime builder");
+
 ASSERT(SIZEOF(.got) == 0, "
 ERROR(imxrt-rt): .got section detected in the input object files
 Dynamic relocations are not supported. If you are linking to C code compiled using
@@ -0,0 +1,155 @@
+//! Checksums of copied sections.
+//!
+//! The runtime builder adds an integrity table to the program. This module
+//! finds that table in the program's flash image, and computes a CRC of each
+//! section described by the table. The target runtime checks the CRCs after
+//! it copies the sections.
+
+use std::error::Error;
+
+use super::compress::{COMPRESSION_MAGIC, STATE_LZ4};
+use super::image::{find_table, read_u32, write_u32};
+
+/// Marks the start of the integrity table.
+pub(crate) const INTEGRITY_MAGIC: u32 = 0x4352_4332;
+
+/// The table's CRCs are computed.
+const STATE_COMPUTED: u32 = 1;
+
+/// Size of the table header, in bytes.
+const HEADER_SIZE: usize = 16;
+/// Size of a table entry, in bytes.
+const ENTRY_SIZE: usize = 16;
+
+/// The lookup table for the (reflected) CRC-32 used by the runtime.
+pub(crate) const CRC32_TABLE: [u32; 256] = {
+    let mut table = [0; 256];
+    let mut idx = 0;
+    while idx < 256 {
+        let mut crc = idx as u32;
+        let mut bit = 0;
+        while bit < 8 {
+            crc = if crc & 1 != 0 {
+                (crc >> 1) ^ 0xEDB8_8320
+            } else {
+                crc >> 1
+            };
+            bit += 1;
+        }
+        table[idx] = crc;
+        idx += 1;
+    }
+    table
+};
+
+fn crc32(bytes: &[u8]) -> u32 {
+    !bytes.iter().fold(!0u32, |crc, byte| {
+        CRC32_TABLE[((crc ^ u32::from(*byte)) & 0xFF) as usize] ^ (crc >> 8)
+    })
+}
+
+/// Compute the checksums of copied sections in `image`.
+///
+/// `image` is the flash contents of a program linked with an
+/// [integrity check](crate::RuntimeBuilder::integrity_check). It starts at the
+/// program's first flash address, like the output of `objcopy -O binary`. This
+/// updates the image in place. Program the updated image into flash.
+///
+/// If you also [compress](crate::compress_image) the image, compute the
+/// checksums first.
+///
+/// # Errors
+///
+/// Returns an error if the image doesn't have an integrity table, if the image is
+/// compressed, or if a section's load image isn't in the image.
+pub fn checksum_image(image: &mut [u8]) -> Result<(), Box<dyn Error>> {
+    let (table, base) = find_table(image, INTEGRITY_MAGIC).ok_or("Image has no integrity table")?;
+    if let Some((compression, _)) = find_table(image, COMPRESSION_MAGIC)
+        && read_u32(image, compression + 8) == Some(STATE_LZ4)
+    {
+        return Err("Compute the checksums before compressing the image".into());
+    }
+
+    let count = read_u32(image, table + 12).ok_or("Truncated table header")? as usize;
+    for idx in 0..count {
+        let offset = table + HEADER_SIZE + idx * ENTRY_SIZE;
+        let field = |idx: usize| read_u32(image, offset + idx * 4).ok_or("Truncated table entry");
+        let (start, end, load) = (field(0)?, field(1)?, field(2)?);
+        // The runtime only checks the sections that it copies. The CRC of an
+        // empty section is zero.
+        if start == load || start == end {
+            continue;
+        }
+        let size = end
+            .checked_sub(start)
+            .ok_or("Section ends before it starts")? as usize;
+        let contents = load
+            .checked_sub(base)
+            .map(|load| load as usize)
+            .and_then(|load| image.get(load..load + size))
+            .ok_or("Section's load image is outside of the image")?;
+        let crc = crc32(contents);
+        write_u32(image, offset + 12, crc);
+    }
+    write_u32(image, table + 8, STATE_COMPUTED);
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{INTEGRITY_MAGIC, checksum_image, crc32};
+
+    #[test]
+    fn crc32_check_value() {
+        assert_eq!(crc32(b"123456789"), 0xCBF4_3926);
+    }
+
+    /// Produce an image at 0x6000_0000 with three sections in the integrity table.
+    ///
+    /// The first section is copied to ITCM. The second executes in place. The
+    /// third is empty.
+    fn test_image() -> (Vec<u8>, Vec<u8>) {
+        let text = b"123456789\0\0\0".to_vec();
+        let mut image = vec![0xFFu8; 64];
+        for word in [
+            INTEGRITY_MAGIC,
+            0x6000_0040,
+            0,
+            3,
+            0x0000_0020,
+            0x0000_0020 + text.len() as u32,
+            0x6000_0080,
+            0,
+            0x6000_0080,
+            0x6000_0084,
+            0x6000_0080,
+            0,
+            0x2000_0000,
+            0x2000_0000,
+            0x6000_0090,
+            0,
+        ] {
+            image.extend_from_slice(&word.to_le_bytes());
+        }
+        image.resize(0x80, 0xFF);
+        image.extend_from_slice(&text);
+        (image, text)
+    }
+
+    #[test]
+    fn checksum_image_table() {
+        let (mut image, text) = test_image();
+        checksum_image(&mut image).unwrap();
+        let word =
+            |offset: usize| u32::from_le_bytes(image[offset..offset + 4].try_into().unwrap());
+        assert_eq!(word(0x48), 1, "CRCs computed");
+        assert_eq!(word(0x5C), crc32(&text));
+        assert_eq!(word(0x6C), 0, "not copied");
+        assert_eq!(word(0x7C), 0, "empty");
+
+        let (mut image, _) = test_image();
+        image.truncate(0x84);
+        assert!(checksum_image(&mut image).is_err(), "truncated section");
+        assert!(checksum_image(&mut [0; 256]).is_err(), "no table");
+    }
+}
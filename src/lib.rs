@@ -151,8 +151,54 @@
 //! is disabled, and that the corresponding memory banks can be used for OCRAM.
 //!
 //! The runtime installs a `cortex-m-rt` `pre_init` function to configure the runtime.
-//! You cannot also define a `pre_init` function, and this crate does not support any
-//! other mechanism for running code before `main()`.
+//! You cannot also define a `pre_init` function. Instead, define a `__imxrt_user_pre_init`
+//! function, described in the next section.
+//!
+//! ## Running code before `main()`
+//!
+//! To run board code before the runtime initializes memory, define an
+//! `__imxrt_user_pre_init` function. You might use this to initialize external memory,
+//! adjust clocks, or put GPIOs into a safe state.
+//!
+//! ```ignore
+//! #[unsafe(no_mangle)]
+//! #[unsafe(link_section = ".xip")]
+//! unsafe extern "C" fn __imxrt_user_pre_init() {
+//!     // Board code.
+//! }
+//! ```
+//!
+//! The runtime calls this function once, before it copies any sections from flash.
+//! By then, the pre-init function has
+//!
+//! 1. configured the watchdogs,
+//! 2. configured FlexRAM and, on 10xx chips and the 1180, restricted the TCM sizes,
+//! 3. captured the reset status, if requested with `reset_reason`,
+//! 4. recorded the first boot timings, if requested with `boot_timings`,
+//! 5. run the core from the ARM PLL, if requested with `core_clock_mhz`,
+//! 6. and configured the FlexSPI AHB buffers, if requested with `flexspi_ahb`.
+//!
+//! The function sees the core clock that you asked for, or the boot ROM's clock
+//! configuration if you didn't ask for one. FlexSPI is still configured for execute
+//! in place, with the AHB buffers that you asked for, or the boot ROM's buffers. On the
+//! 1180, the pre-init function doesn't configure the core clock or the AHB buffers.
+//!
+//! The stack is usable. Since nothing is copied, the function must execute from flash;
+//! place it, and everything that it calls, in the `.xip` section. If the function
+//! changes the FlexSPI configuration, it must not execute from flash while doing so.
+//! The function cannot use `static` data, since `.data`
+//! and `.bss` aren't initialized, and it must not use the heap. Don't rely on
+//! exceptions or interrupts, since the vector table isn't copied. When the function
+//! returns, the runtime copies sections, and `cortex-m-rt` initializes `.data` and
+//! `.bss` before calling `main()`.
+//!
+//! If you don't define the function, the runtime calls a default function that does
+//! nothing.
+//!
+//! C and C++ libraries may need their static constructors, found in `.init_array`, to
+//! run before `main()`. Ask the runtime builder to call them with `init_arrays`. The
+//! constructors run at the end of the pre-init function, after `.data` and `.bss` are
+//! initialized.
 //!
 //! The implementation assumes all flash is FlexSPI.
 //!
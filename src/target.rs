@@ -1,8 +1,13 @@
 //! i.MX RT target support.
 //!
 //! Defines a `cortex-m-rt` pre-init function that disables watchdogs and initializes TCM.
-//! It then copies instructions, read-only data, and the vector table to their intended location.
-//! This only happens if LMAs and VMAs differ.
+//! It then copies instructions, read-only data, the vector table, and any sections described
+//! by the copy table to their intended location. This only happens if LMAs and VMAs differ.
+//! Deferred sections are skipped; the user copies them after `main()` starts. Compressed
+//! sections are decompressed from their load image. If requested, the pre-init function then
+//! initializes static data and calls static constructors, and `cortex-m-rt` skips that work.
+//! If the FlexRAM configuration comes from fuses, the pre-init function doesn't touch the
+//! FlexRAM configuration registers.
 //!
 //! There's a few behaviors worth mentioning:
 //!
@@ -13,10 +18,12 @@
 //!   I could gather, this would be the case if we set fuse values to specify an all-OCRAM config,
 //!   and nothing says we need to flip these bits if the _fuses_ don't allocate xTCM. (Maybe this
 //!   automagically happens? Not sure.)
-//! - We're not changing CM7_xTCMSZ to reflect the xTCM sizes. Again, the setting isn't available
-//!   on the 1170 chips. It's also OK to keep the POR value, since it represents the maximum-possible
-//!   TCM size. This means that users have finer control over xTCM memory sizes, but invalid xTCM accesses
-//!   won't cause a bus fault. See 3.1.3.2. in AN12077 for more discussion.
+//! - By default, we're not changing CM7_xTCMSZ to reflect the xTCM sizes. Again, the setting isn't
+//!   available on the 11xx chips. It's also OK to keep the POR value, since it represents the
+//!   maximum-possible TCM size. This means that users have finer control over xTCM memory sizes, but
+//!   invalid xTCM accesses won't cause a bus fault. See 3.1.3.2. in AN12077 for more discussion.
+//!   Users can opt into the bus faults on 10xx chips; in that case, the implementation writes
+//!   CM7_xTCMSZ after configuring FlexRAM.
 //!
 //! Other notes:
 //!
@@ -31,6 +38,54 @@ use core::{arch::global_asm, ffi::c_void};
 
 pub use cortex_m_rt::*;
 
+/// Returns the value of a linker symbol.
+///
+/// Some symbols, like the size of an optional record, can be zero. Rust assumes that
+/// a static's address is never zero, so read the symbol's value with assembly.
+macro_rules! symbol_value {
+    ($symbol:literal) => {{
+        let value: u32;
+        // Safety: loads a constant into a register.
+        unsafe {
+            core::arch::asm!(
+                concat!("movw {0}, #:lower16:", $symbol),
+                concat!("movt {0}, #:upper16:", $symbol),
+                out(reg) value,
+                options(pure, nomem, nostack, preserves_flags),
+            )
+        };
+        value
+    }};
+}
+
+mod boot_timings;
+mod crash;
+mod deferred;
+mod image;
+mod interrupts;
+mod reset_reason;
+mod retained;
+mod unwind;
+mod vector_table;
+pub use boot_timings::{BootTimings, boot_timings};
+pub use crash::{CRASH_STACK_WORDS, CrashRecord, clear_crash_record, crash_record};
+pub use deferred::{DeferredLoad, Section, deferred_loads, load_deferred};
+pub use image::{
+    Family, FlexRamKind, FlexRamLayout, Memory, Placements, VerifyFlexRamError,
+    configured_flexram_layout, family, flexram_layout, placements, verify_flexram_layout,
+};
+pub use interrupts::{
+    InterruptHandler, InvalidInterrupt, interrupt_count, register_interrupt, unregister_interrupt,
+};
+pub use reset_reason::{ResetReason, clear_reset_status, reset_reason, reset_status};
+pub use retained::{Retained, RetainedRef, Retention};
+pub use unwind::{Backtrace, Frame, backtrace};
+pub use vector_table::{
+    RelocateError, VectorTable, active_vector_table, relocate_vector_table,
+    relocate_vector_table_to_reserved, restore_vector_table, runtime_vector_table,
+    set_vector_table,
+};
+
 global_asm! {r#"
 .cfi_sections .debug_frame
 .section .__pre_init,"ax"
@@ -39,13 +94,20 @@ global_asm! {r#"
 .thumb_func
 .cfi_startproc
 
-.macro copy_section dst, src, end
-    ldr r0, =\dst
-    ldr r2, =\src
-    cmp r2, r0
-    beq 999f
-
-    ldr r1, =\end
+@ Copy words from r2 to r0, until r0 equals r1. The copy moves 32 bytes per
+@ iteration, then copies the remaining words one at a time. Clobbers r3-r11.
+@ The runtime builder aligns the runtime's sections to 32 bytes, so each burst
+@ moves one cache line. The copy is still correct for word-aligned sections.
+.macro copy_words
+    sub r3, r1, r0
+    bic r3, r3, #31
+    add r3, r3, r0                  @ r3 = end of the 32-byte bursts.
+    777:
+    cmp r3, r0
+    beq 888f
+    ldm r2!, {{r4-r11}}
+    stm r0!, {{r4-r11}}
+    b 777b
     888:
     cmp r1, r0
     beq 999f
@@ -55,25 +117,162 @@ global_asm! {r#"
     999:
 .endm
 
-__pre_init:
-    ldr r0, =__imxrt_rt_v0.2        @ Need to know which chip family we're initializing.
-    ldr r1, =0x1180
-    cmp r0, r1                      @ Is this an 1180?
-    beq 1180f
+@ Zero words from r0, until r0 equals r1. Like copy_words, the loop stores 32 bytes per
+@ iteration, then stores the remaining words one at a time. Clobbers r3-r11.
+.macro zero_words
+    movs r4, #0
+    movs r5, #0
+    movs r6, #0
+    movs r7, #0
+    movs r8, #0
+    movs r9, #0
+    movs r10, #0
+    movs r11, #0
+    sub r3, r1, r0
+    bic r3, r3, #31
+    add r3, r3, r0                  @ r3 = end of the 32-byte bursts.
+    777:
+    cmp r3, r0
+    beq 888f
+    stm r0!, {{r4-r11}}
+    b 777b
+    888:
+    cmp r1, r0
+    beq 999f
+    stm r0!, {{r4}}
+    b 888b
+    999:
+.endm
+
+@ Record the cycle count in the boot timings, at the given byte offset. Clobbers r0 and r1.
+.macro timestamp offset
+    ldr r0, =__boot_timings_size
+    cbz r0, 666f
+    ldr r0, =0xE0001004             @ DWT_CYCCNT
+    ldr r0, [r0]
+    ldr r1, =__boot_timings
+    str r0, [r1, #\offset]
+    666:
+.endm
+
+@ Select a watchdog's base address, given the chip family in r0. Sets r2, and clobbers r1.
+.macro watchdog_base base11xx, base10xx
     ldr r1, =0x1100
     cmp r0, r1                      @ Is this an 1160 or 1170?
-
-    # Disable RTWODOG3.
     ite gt
-    ldrgt r2, =0x40038000           @ RTWDOG base address for 11xx chips...
-    ldrle r2, =0x400BC000           @ RTWDOG base address for 10xx chips...
+    ldrgt r2, =\base11xx
+    ldrle r2, =\base10xx
+.endm
+
+@ Configure the WDOG at r2. The configuration is zero to leave it alone, one to disable it,
+@ or the value for WDOG[WCR] that enables it. Clobbers r1 and r3.
+.macro wdog config
+    ldr r1, =\config
+    cbz r1, 555f                    @ Leave the watchdog alone.
+    ldrh r3, [r2, #8]
+    bic r3, r3, #1
+    strh r3, [r2, #8]               @ WDOG[WMCR][PDE] = 0, disabling the power-down counter.
+    cmp r1, #1
+    bne 554f
+    ldrh r1, [r2]
+    bic r1, r1, #1<<2               @ Clear WDE. No effect if the watchdog is already enabled.
+    strh r1, [r2]                   @ WDOG[WCR] = r1
+    b 555f
+    554:
+    strh r1, [r2]                   @ WDOG[WCR] = __wdogN_config, enabling the watchdog.
+    movw r1, #0x5555
+    strh r1, [r2, #2]
+    movw r1, #0xAAAA
+    strh r1, [r2, #2]               @ Service the watchdog, loading the new timeout.
+    555:
+.endm
+
+@ Configure the RTWDOG at r2. The configuration is zero to leave it alone, one to disable it,
+@ or the value for RTWDOG[CS] that enables it. Clobbers r1 and r3.
+.macro rtwdog config, toval
+    ldr r1, =\config
+    cbz r1, 559f                    @ Leave the watchdog alone.
     ldr r3, =0xD928C520             @ RTWDOG magic number
     str r3, [r2, #4]                @ RTWDOG[CNT] = 0xD928C520.
+    cmp r1, #1
+    bne 557f
     ldr r3, [r2]                    @ r3 = RTWDOG[CS]
     bic r3, r3, #1<<7               @ r3 = r3 & !(1 << 7), clears enable.
     str r3, [r2]                    @ RTWDOG[CS] = r3
+    b 559f
+    557:
+    ldr r3, [r2]
+    tst r3, #1<<11
+    beq 557b                        @ Wait for RTWDOG[CS][ULK].
+    ldr r3, =\toval
+    str r3, [r2, #8]                @ RTWDOG[TOVAL] = toval
+    str r1, [r2]                    @ RTWDOG[CS] = config, enabling the watchdog.
+    558:
+    ldr r3, [r2]
+    tst r3, #1<<10
+    beq 558b                        @ Wait for RTWDOG[CS][RCS].
+    559:
+.endm
+
+@ Save the cycle count in r12, if measuring the boot timings. Clobbers r3.
+.macro watchdog_timestamp
+    ldr r3, =__boot_timings_size
+    cbz r3, 556f
+    ldr r3, =0xE0001004             @ DWT_CYCCNT
+    ldr r12, [r3]
+    556:
+.endm
+
+.macro copy_section dst, src, end
+    ldr r0, =\dst
+    ldr r1, =\end
+    ldr r2, =\src
+    cmp r2, r0
+    it eq
+    moveq r1, r0                    @ Already in place; copy nothing.
+    copy_words
+.endm
+
+__pre_init:
+    # Start the cycle counter, if measuring the boot timings.
+    mov r12, #0                     @ Watchdog timestamp, if any. Recorded once FlexRAM is ready.
+    ldr r0, =__boot_timings_size
+    cbz r0, 1004f
+    ldr r0, =0xE000EDFC             @ DEMCR
+    ldr r1, [r0]
+    orr r1, r1, #1<<24              @ TRCENA
+    str r1, [r0]
+    ldr r0, =0xE0001000             @ DWT base address
+    ldr r1, =0xC5ACCE55
+    str r1, [r0, #0xFB0]            @ DWT_LAR = 0xC5ACCE55, unlocking DWT registers.
+    movs r1, #0
+    str r1, [r0, #4]                @ DWT_CYCCNT = 0
+    ldr r1, [r0]
+    orr r1, r1, #1                  @ CYCCNTENA
+    str r1, [r0]
+    1004:
+
+    ldr r0, =__imxrt_rt_v0.2        @ Need to know which chip family we're initializing.
+    ldr r1, =0x1180
+    cmp r0, r1                      @ Is this an 1180?
+    beq 1180f
+
+    # Configure WDOG1 and WDOG2.
+    watchdog_base 0x40030000, 0x400B8000
+    wdog __wdog1_config
+    watchdog_base 0x40034000, 0x400D0000
+    wdog __wdog2_config
 
-    # Prepare FlexRAM regions.
+    # Configure RTWDOG3.
+    watchdog_base 0x40038000, 0x400BC000
+    rtwdog __rtwdog_config, __rtwdog_toval
+    watchdog_timestamp
+    ldr r1, =0x1100
+    cmp r0, r1                      @ Is this an 1160 or 1170?
+
+    # Prepare FlexRAM regions, unless we're using the fuse-defined configuration.
+    ldr r1, =__flexram_from_fuses
+    cbnz r1, 1001f                  @ Fuses, or a bootloader, configured FlexRAM. Leave GPR16/17/18 alone.
     ldr r0, =0x400AC000             @ IMXRT_IOMUXC_GPR base address for 10xx chips, overwritten if actually 11xx...
     ldr r1, =__flexram_config       @ Value for GPR17 (and GPR18 for 11xx)
     itttt gt                        @ Need a few extra operations to handle 11xx split banks.
@@ -85,23 +284,479 @@ __pre_init:
     ldr r1, [r0, #64]               @ r1 = *(IMXRT_IOMUXC_GPR + 16)
     orr r1, r1, #1<<2               @ r1 |= 1 << 2
     str r1, [r0, #64]               @ *(IMXRT_IOMUXC_GPR + 16) = r1
+
+    # Restrict the TCM sizes, if requested. The mask is only non-zero for 10xx chips.
+    1001:
+    ldr r1, =__tcm_size_mask
+    cbz r1, 1005f                   @ Keep the maximum-possible TCM sizes.
+    ldr r0, =0x400AC000             @ IMXRT_IOMUXC_GPR base address for 10xx chips.
+    ldr r2, [r0, #56]               @ r2 = *(IMXRT_IOMUXC_GPR + 14)
+    bic r2, r2, r1                  @ r2 &= ~__tcm_size_mask
+    ldr r1, =__tcm_size_config
+    orr r2, r2, r1                  @ r2 |= __tcm_size_config
+    str r2, [r0, #56]               @ *(IMXRT_IOMUXC_GPR + 14) = r2
+    1005:
     b 1000f
 
     1180:
-    ldr r0, =0x444F0060             @ M33_CONFIG
-    ldr r1, =__flexram_config
-    str r1, [r0, #0]
+    # Configure the CM33's WDOG1 and WDOG2, which are RTWDOGs.
+    ldr r2, =0x442D0000             @ RTWDOG1
+    rtwdog __wdog1_config, __wdog1_toval
+    ldr r2, =0x442E0000             @ RTWDOG2
+    rtwdog __wdog2_config, __wdog2_toval
+    watchdog_timestamp
+
+    # Size the TCMs, unless we're using the fuse-defined configuration.
+    ldr r1, =__flexram_from_fuses
+    cbnz r1, 1000f                  @ Fuses, or a bootloader, configured FlexRAM. Leave M33_CFG alone.
+    ldr r0, =0x444F0060             @ BLK_CTRL_S_AONMIX[M33_CFG]
+    ldr r1, [r0]
+    bic r1, r1, #0b11<<3            @ Clear TCM_SIZE.
+    ldr r2, =__flexram_config
+    orr r1, r1, r2, lsl #3          @ TCM_SIZE = __flexram_config
+    str r1, [r0]
 
     1000:
+    push {{r3-r11, lr}}             @ Saved for the copies, and for the user's hook.
+
+    # Capture the reset status, if requested. Configuring the watchdogs and FlexRAM
+    # doesn't change the status, and .uninit is usable once FlexRAM is configured.
+    ldr r0, =__reset_reason_size
+    cbz r0, 1020f
+    ldr r0, =__src_srsr
+    ldr r1, [r0]                    @ Read only. Firmware decides when to clear the flags.
+    ldr r0, =__reset_reason
+    str r1, [r0]
+    1020:
+
+    ldr r0, =__boot_timings_size
+    cbz r0, 1006f
+    ldr r0, =__boot_timings
+    str r12, [r0, #0]               @ Watchdogs disabled.
+    1006:
+    timestamp 4                     @ FlexRAM configured.
+
+    # Run the core from the ARM PLL, if requested. The divider is zero on chips
+    # that don't support this.
+    ldr r1, =__arm_pll_div_select
+    cmp r1, #0
+    beq 1010f
+    ldr r0, =__imxrt_rt_v0.2
+    ldr r1, =0x1100
+    cmp r0, r1                      @ Is this an 1160 or 1170?
+    bgt 1030f
+    ldr r0, =0x40080000             @ DCDC base address
+    ldr r2, [r0, #12]               @ r2 = DCDC[REG3]
+    and r3, r2, #0x1F               @ r3 = DCDC[REG3][TRG]
+    ldr r1, =__dcdc_trg
+    cmp r3, r1
+    bhs 1011f                       @ The core voltage is high enough.
+    bic r2, r2, #0x1F
+    orr r2, r2, r1
+    str r2, [r0, #12]               @ DCDC[REG3][TRG] = __dcdc_trg
+    1012:
+    ldr r2, [r0]
+    tst r2, #1<<31
+    beq 1012b                       @ Wait for DCDC[REG0][STS_DC_OK].
+    1011:
+    ldr r0, =0x400FC000             @ CCM base address
+    ldr r2, [r0, #0x18]
+    bic r2, r2, #3<<12
+    orr r2, r2, #1<<12
+    str r2, [r0, #0x18]             @ CCM[CBCMR][PERIPH_CLK2_SEL] = oscillator
+    1013:
+    ldr r2, [r0, #0x48]
+    tst r2, #1<<3
+    bne 1013b                       @ Wait for CCM[CDHIPR][PERIPH2_CLK_SEL_BUSY] to clear.
+    ldr r2, [r0, #0x14]
+    bic r2, r2, #7<<27              @ CCM[CBCDR][PERIPH_CLK2_PODF] = 0, divide by 1.
+    orr r2, r2, #1<<25              @ CCM[CBCDR][PERIPH_CLK_SEL] = 1, run from the oscillator.
+    str r2, [r0, #0x14]
+    1014:
+    ldr r2, [r0, #0x48]
+    tst r2, #1<<5
+    bne 1014b                       @ Wait for CCM[CDHIPR][PERIPH_CLK_SEL_BUSY] to clear.
+    ldr r1, =0x400D8000             @ CCM_ANALOG base address
+    mov r2, #1<<12
+    str r2, [r1]                    @ CCM_ANALOG[PLL_ARM] = POWERDOWN
+    ldr r2, =__arm_pll_div_select
+    orr r2, r2, #1<<13
+    str r2, [r1]                    @ CCM_ANALOG[PLL_ARM] = ENABLE | __arm_pll_div_select
+    1015:
+    ldr r2, [r1]
+    tst r2, #1<<31
+    beq 1015b                       @ Wait for CCM_ANALOG[PLL_ARM][LOCK].
+    ldr r2, =__arm_podf
+    str r2, [r0, #0x10]             @ CCM[CACRR][ARM_PODF] = __arm_podf
+    1016:
+    ldr r2, [r0, #0x48]
+    tst r2, #1<<16
+    bne 1016b                       @ Wait for CCM[CDHIPR][ARM_PODF_BUSY] to clear.
+    ldr r2, [r0, #0x14]
+    bic r2, r2, #0x1F<<8            @ CCM[CBCDR][AHB_PODF] = 0, divide by 1.
+    ldr r3, =__ipg_podf
+    orr r2, r2, r3, lsl #8          @ CCM[CBCDR][IPG_PODF] = __ipg_podf
+    str r2, [r0, #0x14]
+    1017:
+    ldr r2, [r0, #0x48]
+    tst r2, #1<<1
+    bne 1017b                       @ Wait for CCM[CDHIPR][AHB_PODF_BUSY] to clear.
+    ldr r2, [r0, #0x18]
+    orr r2, r2, #3<<18
+    str r2, [r0, #0x18]             @ CCM[CBCMR][PRE_PERIPH_CLK_SEL] = divided ARM PLL
+    ldr r2, [r0, #0x14]
+    bic r2, r2, #1<<25
+    str r2, [r0, #0x14]             @ CCM[CBCDR][PERIPH_CLK_SEL] = 0, run from the ARM PLL.
+    1018:
+    ldr r2, [r0, #0x48]
+    tst r2, #1<<5
+    bne 1018b                       @ Wait for CCM[CDHIPR][PERIPH_CLK_SEL_BUSY] to clear.
+    b 1010f
+
+    1030:
+    ldr r0, =0x40CA8000             @ DCDC base address
+    ldr r2, [r0, #4]                @ r2 = DCDC[CTRL1]
+    ubfx r3, r2, #8, #5             @ r3 = DCDC[CTRL1][VDD1P0CTRL_TRG]
+    ldr r1, =__dcdc_trg
+    cmp r3, r1
+    bhs 1031f                       @ The core voltage is high enough.
+    ldr r3, [r0, #20]
+    bic r3, r3, #1<<29
+    str r3, [r0, #20]               @ DCDC[REG3][VDD1P0CTRL_DISABLE_STEP] = 0
+    bfi r2, r1, #8, #5
+    str r2, [r0, #4]                @ DCDC[CTRL1][VDD1P0CTRL_TRG] = __dcdc_trg
+    1032:
+    ldr r2, [r0, #8]
+    tst r2, #1<<31
+    beq 1032b                       @ Wait for DCDC[REG0][STS_DC_OK].
+    1031:
+    ldr r0, =0x40CC0000             @ CCM_CLOCK_ROOT0 (M7) base address
+    movs r2, #0
+    str r2, [r0]                    @ CCM_CLOCK_ROOT0[CONTROL] = OSC_RC_48M_DIV2, divide by 1.
+    1033:
+    ldr r2, [r0, #0x20]
+    tst r2, #1<<28
+    bne 1033b                       @ Wait for CCM_CLOCK_ROOT0[STATUS0][SLICE_BUSY] to clear.
+    ldr r1, =0x40C84200             @ ANADIG_PLL_ARM_PLL_CTRL
+    ldr r2, [r1]
+    bic r2, r2, #3<<13              @ Clear POWERUP and ENABLE_CLK.
+    orr r2, r2, #1<<30              @ Set ARM_PLL_GATE.
+    str r2, [r1]                    @ Power down the PLL, and gate its output.
+    bic r2, r2, #0xFF
+    bic r2, r2, #7<<15              @ Clear DIV_SELECT, POST_DIV_SEL, and BYPASS.
+    ldr r3, =__arm_pll_div_select
+    orr r2, r2, r3
+    ldr r3, =__arm_podf
+    orr r2, r2, r3, lsl #15
+    orr r2, r2, #1<<13
+    str r2, [r1]                    @ POWERUP, with __arm_pll_div_select and __arm_podf (POST_DIV_SEL).
+    dsb
+    isb
+    movw r3, #720
+    1034:
+    subs r3, r3, #1
+    bne 1034b                       @ Wait at least 30us at 24MHz before checking stability.
+    1035:
+    ldr r3, [r1]
+    tst r3, #1<<29
+    beq 1035b                       @ Wait for ANADIG_PLL_ARM_PLL_CTRL[ARM_PLL_STABLE].
+    orr r2, r2, #1<<14
+    bic r2, r2, #1<<30
+    str r2, [r1]                    @ Set ENABLE_CLK, and clear ARM_PLL_GATE.
+    mov r2, #4<<8
+    str r2, [r0]                    @ CCM_CLOCK_ROOT0[CONTROL] = ARM_PLL_OUT, divide by 1.
+    1036:
+    ldr r2, [r0, #0x20]
+    tst r2, #1<<28
+    bne 1036b                       @ Wait for CCM_CLOCK_ROOT0[STATUS0][SLICE_BUSY] to clear.
+    1010:
+    timestamp 8                     @ Core clock configured.
+
+    # Configure the FlexSPI AHB buffers, if requested. The routine can't execute from
+    # flash, so copy it into RAM.
+    copy_section __sflexspi_ahb     , __siflexspi_ahb   , __eflexspi_ahb
+    ldr r3, =__sflexspi_ahb
+    cbz r3, 1019f                   @ Keep the boot ROM's configuration.
+    dsb
+    isb
+    orr r3, r3, #1                  @ Thumb state.
+    blx r3
+    1019:
+    timestamp 12                    @ FlexSPI AHB buffers configured.
+
+    # Call the user's hook. It executes from flash, since nothing is copied yet.
+    ldr r3, =__imxrt_user_pre_init
+    blx r3
+    timestamp 16                    @ User's hook returned.
+    ldr r0, =__skip_text_copy
+    cbnz r0, 1002f                  @ Deferred or compressed instructions.
     copy_section __stext            , __sitext          , __etext
+    copy_section __stext_spill      , __sitext_spill    , __etext_spill
+    1002:
+    timestamp 20                    @ Instructions copied.
     copy_section __svector_table    , __sivector_table  , __evector_table
+    ldr r0, =__svector_table_reserved
+    ldr r1, =__evector_table_reserved
+    ldr r2, =DefaultHandler
+    1021:
+    cmp r0, r1
+    beq 1022f
+    str r2, [r0], #4                @ Point the reserved interrupts at DefaultHandler.
+    b 1021b
+    1022:
+    timestamp 24                    @ Vector table copied.
+    ldr r0, =__skip_rodata_copy
+    cbnz r0, 1003f                  @ Deferred or compressed read-only data.
     copy_section __srodata          , __sirodata        , __erodata
+    1003:
+    timestamp 28                    @ Read-only data copied.
+
+    # Copy the sections described by the copy table. Each entry is the start VMA,
+    # end VMA, and LMA of a section.
+    ldr r12, =__scopy_table
+    2000:
+    ldr r3, =__ecopy_table
+    cmp r12, r3
+    beq 2002f
+    ldm r12!, {{r0, r1, r2}}
+    cmp r2, r0
+    it eq
+    moveq r1, r0                    @ Already in place; copy nothing.
+    copy_words
+    b 2000b
+    2002:
+    timestamp 32                    @ Copy table sections copied.
+
+    # Load the sections described by the compression table. Each entry is the start VMA,
+    # end VMA, LMA, and size of the load image. If the post-link step didn't compress
+    # the sections, the load image is a copy of the section.
+    ldr r12, =__scompression_table
+    ldr r3, =__ecompression_table
+    cmp r12, r3
+    beq 3009f                       @ Nothing is compressed.
+    add r12, r12, #20               @ Skip the header.
+    3000:
+    ldr r3, =__ecompression_table
+    cmp r12, r3
+    beq 3009f
+    ldm r12!, {{r0, r1, r2, r4}}    @ r0 = dst, r1 = dst end, r2 = src, r4 = src size.
+    add r4, r4, r2                  @ r4 = src end.
+    ldr r3, =__scompression_table
+    ldr r3, [r3, #8]                @ r3 = 0 if stored, or 1 if LZ4 compressed.
+    cbnz r3, 3002f
+    copy_words                      @ Stored; copy words.
+    b 3000b
+    3002:                           @ LZ4 block; decode the next sequence.
+    cmp r2, r4
+    bhs 3000b
+    ldrb r3, [r2], #1               @ r3 = token.
+    lsrs r5, r3, #4                 @ r5 = literal length.
+    cmp r5, #15
+    bne 3004f
+    3003:
+    ldrb r6, [r2], #1
+    add r5, r5, r6
+    cmp r6, #255
+    beq 3003b
+    3004:
+    cbz r5, 3006f
+    3005:                           @ Copy literals.
+    ldrb r6, [r2], #1
+    strb r6, [r0], #1
+    subs r5, r5, #1
+    bne 3005b
+    3006:
+    cmp r2, r4
+    bhs 3000b                       @ The last sequence has no match.
+    ldrb r5, [r2], #1
+    ldrb r6, [r2], #1
+    orr r5, r5, r6, lsl #8          @ r5 = match offset.
+    sub r5, r0, r5                  @ r5 = match src.
+    and r3, r3, #15                 @ r3 = match length - 4.
+    cmp r3, #15
+    bne 3008f
+    3007:
+    ldrb r6, [r2], #1
+    add r3, r3, r6
+    cmp r6, #255
+    beq 3007b
+    3008:
+    add r3, r3, #4
+    3011:                           @ Copy the match. It may overlap the output.
+    ldrb r6, [r5], #1
+    strb r6, [r0], #1
+    subs r3, r3, #1
+    bne 3011b
+    b 3002b
+    3009:
+    timestamp 36                    @ Compressed sections loaded.
+
+    # Check the copied sections against the integrity table. Each entry is the start VMA,
+    # end VMA, LMA, and CRC of a section. If the post-link step didn't compute the CRCs,
+    # there's nothing to check.
+    ldr r12, =__sintegrity_table
+    ldr r3, =__eintegrity_table
+    cmp r12, r3
+    beq 4009f                       @ No integrity table.
+    ldr r3, [r12, #8]               @ r3 = 1 if the CRCs are computed.
+    cmp r3, #0
+    beq 4009f
+    add r12, r12, #16               @ Skip the header.
+    4000:
+    ldr r3, =__eintegrity_table
+    cmp r12, r3
+    beq 4009f
+    ldm r12!, {{r0, r1, r2, r3}}    @ r0 = start, r1 = end, r2 = LMA, r3 = expected CRC.
+    cmp r2, r0
+    beq 4000b                       @ Not copied; not checked.
+    ldr r5, =__integrity_crc_table
+    mvn r4, #0                      @ r4 = CRC.
+    mov r9, r0
+    4001:
+    cmp r9, r1
+    bhs 4002f
+    ldr r6, [r9], #4
+    .rept 4                         @ Update the CRC with each byte, LSB first.
+    eor r7, r4, r6
+    uxtb r7, r7
+    ldr r7, [r5, r7, lsl #2]
+    eor r4, r7, r4, lsr #8
+    lsr r6, r6, #8
+    .endr
+    b 4001b
+    4002:
+    mvn r4, r4
+    cmp r4, r3
+    beq 4000b
+    ldr r3, =IntegrityCheckFailed   @ r0 = start of the corrupt section.
+    blx r3
+    4009:
+    timestamp 40                    @ Copied sections checked.
+
+    # Initialize .data and .bss, if requested for static constructors or boot timings. This
+    # is the work that cortex-m-rt would do next. The linker script makes sure that
+    # cortex-m-rt doesn't redo it.
+    ldr r0, =__init_statics
+    cmp r0, #0
+    beq 5001f
+    copy_section __sdata, __sidata_runtime, __edata
+    ldr r0, =__sbss
+    ldr r1, =__ebss_runtime
+    zero_words
+    5001:
+    timestamp 44                    @ Statics initialized.
+
+    # Call static constructors, if requested. They expect a usable FPU, which cortex-m-rt
+    # would enable next.
+    ldr r0, =__call_init_arrays
+    cbz r0, 5009f
+    ldr r0, =0xE000ED88             @ SCB_CPACR
+    ldr r1, [r0]
+    orr r1, r1, #0xF<<20
+    str r1, [r0]                    @ Full access to CP10 and CP11, enabling the FPU.
+    dsb
+    isb
+    ldr r4, =__preinit_array_start
+    ldr r5, =__init_array_end       @ The init array follows the pre-init array.
+    5002:
+    cmp r4, r5
+    beq 5009f
+    ldr r3, [r4], #4
+    blx r3
+    b 5002b
+    5009:
+    timestamp 48                    @ Static constructors called.
+    pop {{r3-r11, lr}}
 
     # All done; back to the reset handler.
     bx lr
 
 .cfi_endproc
 .size __pre_init, . - __pre_init
+
+@ The default user pre-init hook. Does nothing.
+.global __imxrt_rt_user_pre_init
+.type __imxrt_rt_user_pre_init,%function
+.thumb_func
+__imxrt_rt_user_pre_init:
+    bx lr
+.size __imxrt_rt_user_pre_init, . - __imxrt_rt_user_pre_init
+
+@ The default handler for a failed integrity check. Resets the processor.
+.global __imxrt_rt_integrity_check_failed
+.type __imxrt_rt_integrity_check_failed,%function
+.thumb_func
+__imxrt_rt_integrity_check_failed:
+    dsb
+    ldr r0, =0xE000ED0C             @ AIRCR
+    ldr r1, =0x05FA0004             @ VECTKEY | SYSRESETREQ
+    str r1, [r0]
+    dsb
+    b .
+.size __imxrt_rt_integrity_check_failed, . - __imxrt_rt_integrity_check_failed
+
+@ Configure the FlexSPI AHB buffers. The runtime copies this routine into RAM, since the
+@ controller can't serve AHB reads while it's reconfigured. Clobbers r0-r2.
+.section .imxrt_rt_flexspi_ahb,"ax"
+.global __imxrt_rt_flexspi_ahb
+.type __imxrt_rt_flexspi_ahb,%function
+.thumb_func
+__imxrt_rt_flexspi_ahb:
+    ldr r0, =__flexspi_address
+    dsb
+    isb
+    1:
+    ldr r1, [r0, #0xE0]
+    and r1, r1, #3
+    cmp r1, #3
+    bne 1b                          @ Wait for FLEXSPI[STS0][SEQIDLE] and FLEXSPI[STS0][ARBIDLE].
+    ldr r1, [r0]
+    orr r1, r1, #1<<1
+    str r1, [r0]                    @ FLEXSPI[MCR0][MDIS] = 1
+    ldr r1, [r0, #0x0C]
+    bic r1, r1, #1<<5
+    ldr r2, =__flexspi_ahbcr_prefetch
+    orr r1, r1, r2
+    str r1, [r0, #0x0C]             @ FLEXSPI[AHBCR][PREFETCHEN] = __flexspi_ahbcr_prefetch
+    ldr r1, =__flexspi_ahbrxbuf0cr0
+    str r1, [r0, #0x20]
+    ldr r1, =__flexspi_ahbrxbuf1cr0
+    str r1, [r0, #0x24]
+    ldr r1, =__flexspi_ahbrxbuf2cr0
+    str r1, [r0, #0x28]
+    ldr r1, =__flexspi_ahbrxbuf3cr0
+    str r1, [r0, #0x2C]
+    ldr r1, =__flexspi_ahbrxbuf_count
+    cmp r1, #8
+    bne 2f                          @ Only 11xx chips have buffers 4 through 7.
+    ldr r1, =__flexspi_ahbrxbuf4cr0
+    str r1, [r0, #0x30]
+    ldr r1, =__flexspi_ahbrxbuf5cr0
+    str r1, [r0, #0x34]
+    ldr r1, =__flexspi_ahbrxbuf6cr0
+    str r1, [r0, #0x38]
+    ldr r1, =__flexspi_ahbrxbuf7cr0
+    str r1, [r0, #0x3C]
+    2:
+    ldr r1, [r0]
+    bic r1, r1, #1<<1
+    str r1, [r0]                    @ FLEXSPI[MCR0][MDIS] = 0
+    orr r1, r1, #1
+    str r1, [r0]                    @ FLEXSPI[MCR0][SWRESET] = 1, clearing the buffers.
+    3:
+    ldr r1, [r0]
+    tst r1, #1
+    bne 3b                          @ Wait for the reset to finish.
+    4:
+    ldr r1, [r0, #0xE0]
+    and r1, r1, #3
+    cmp r1, #3
+    bne 4b                          @ Wait for the controller to idle.
+    dsb
+    isb
+    bx lr
+.ltorg
+.size __imxrt_rt_flexspi_ahb, . - __imxrt_rt_flexspi_ahb
 "#
 }
 
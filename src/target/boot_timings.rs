@@ -0,0 +1,99 @@
+//! Boot phase measurements.
+
+/// Cycle counts recorded while the runtime boots.
+///
+/// Each field is the value of the DWT cycle counter at the end of a boot phase.
+/// The counter starts at zero when the runtime's pre-init function starts, so
+/// the measurements don't include the boot ROM. The phases run in the order of
+/// the fields. Subtract adjacent fields to find the duration of a phase.
+///
+/// If the runtime skipped a phase, the phase takes almost no time.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct BootTimings {
+    /// Watchdogs are configured.
+    pub watchdog: u32,
+    /// FlexRAM is configured.
+    pub flexram: u32,
+    /// The core clock is configured.
+    ///
+    /// Later phases count cycles at the new core frequency.
+    pub core_clock: u32,
+    /// The FlexSPI AHB buffers are configured.
+    pub flexspi_ahb: u32,
+    /// The user's pre-init hook returned.
+    pub user_pre_init: u32,
+    /// Instructions are copied.
+    pub text: u32,
+    /// The vector table is copied.
+    pub vector_table: u32,
+    /// Read-only data is copied.
+    pub rodata: u32,
+    /// The sections described by the copy table are copied.
+    pub copy_table: u32,
+    /// The compressed sections are loaded.
+    pub compressed: u32,
+    /// The copied sections are checked.
+    pub integrity: u32,
+    /// `.data` is initialized, and `.bss` is zeroed.
+    ///
+    /// When it measures boot timings, the runtime initializes these statics itself,
+    /// instead of leaving the work to `cortex-m-rt`.
+    pub statics: u32,
+    /// Static constructors returned.
+    ///
+    /// This is the end of the runtime's pre-init function.
+    pub init_arrays: u32,
+    /// The cycle count when you called [`boot_timings`].
+    ///
+    /// Call `boot_timings` at the start of `main()` to measure the time between
+    /// the pre-init function and `main()`.
+    pub main: u32,
+}
+
+/// Returns the cycle counts recorded while the runtime booted.
+///
+/// Returns `None` if the runtime builder didn't enable boot timings.
+pub fn boot_timings() -> Option<BootTimings> {
+    unsafe extern "C" {
+        static __boot_timings: [u32; 13];
+    }
+    let size = symbol_value!("__boot_timings_size");
+    if size == 0 {
+        return None;
+    }
+    // Safety: DWT_CYCCNT is always readable. Reading it has no side effects.
+    let main = unsafe { (0xE000_1004 as *const u32).read_volatile() };
+    // Safety: the runtime writes this record before main, and nothing
+    // else should write it.
+    let [
+        watchdog,
+        flexram,
+        core_clock,
+        flexspi_ahb,
+        user_pre_init,
+        text,
+        vector_table,
+        rodata,
+        copy_table,
+        compressed,
+        integrity,
+        statics,
+        init_arrays,
+    ] = unsafe { (&raw const __boot_timings).read_volatile() };
+    Some(BootTimings {
+        watchdog,
+        flexram,
+        core_clock,
+        flexspi_ahb,
+        user_pre_init,
+        text,
+        vector_table,
+        rodata,
+        copy_table,
+        compressed,
+        integrity,
+        statics,
+        init_arrays,
+        main,
+    })
+}
@@ -0,0 +1,230 @@
+//! Crash records that survive a reset.
+
+use core::arch::global_asm;
+
+use super::retained::crc32_update;
+
+/// Marks a valid crash record. Mixed with the size of the record.
+const MAGIC: u32 = 0x4352_5348;
+
+/// The number of stack words in a [`CrashRecord`].
+pub const CRASH_STACK_WORDS: usize = 32;
+
+/// The state of the processor when it took a `HardFault`.
+///
+/// The runtime's `HardFault` handler writes this record into the `.retained`
+/// section, then resets the processor. Use [`crash_record`] to read the record
+/// on the next boot.
+#[repr(C)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct CrashRecord {
+    /// The registers that the processor stacked when it took the exception.
+    ///
+    /// In order, these are r0, r1, r2, r3, r12, lr, pc, and xPSR.
+    pub frame: [u32; 8],
+    /// The address of the exception frame.
+    pub sp: u32,
+    /// The `EXC_RETURN` value of the exception.
+    ///
+    /// If bit 2 is set, the exception frame is on the process stack. If bit 4
+    /// is clear, the processor also stacked floating-point registers.
+    pub exc_return: u32,
+    /// The Configurable Fault Status Register (CFSR).
+    pub cfsr: u32,
+    /// The HardFault Status Register (HFSR).
+    pub hfsr: u32,
+    /// The MemManage Fault Address Register (MMFAR).
+    ///
+    /// Only valid if `cfsr` indicates MMARVALID.
+    pub mmfar: u32,
+    /// The BusFault Address Register (BFAR).
+    ///
+    /// Only valid if `cfsr` indicates BFARVALID.
+    pub bfar: u32,
+    /// The number of valid words in `stack`.
+    pub stack_len: u32,
+    /// The stack words that follow the eight-word exception frame.
+    ///
+    /// If the processor stacked floating-point registers, these words start
+    /// with the floating-point registers. The handler only copies words from
+    /// the main stack; if the exception frame is on the process stack, this
+    /// is empty.
+    pub stack: [u32; CRASH_STACK_WORDS],
+}
+
+impl CrashRecord {
+    /// The stacked program counter. This is typically the faulting instruction.
+    pub const fn pc(&self) -> u32 {
+        self.frame[6]
+    }
+    /// The stacked link register.
+    pub const fn lr(&self) -> u32 {
+        self.frame[5]
+    }
+    /// The stacked program status register.
+    pub const fn xpsr(&self) -> u32 {
+        self.frame[7]
+    }
+    /// The valid words of the stack snippet.
+    pub fn stack(&self) -> &[u32] {
+        let len = (self.stack_len as usize).min(CRASH_STACK_WORDS);
+        &self.stack[..len]
+    }
+}
+
+/// The crash record's storage, reserved by the linker script.
+///
+/// Keep the size of this struct in sync with the runtime builder.
+#[repr(C)]
+struct Storage {
+    magic: u32,
+    crc: u32,
+    record: CrashRecord,
+}
+
+/// The marker for a valid crash record.
+const fn magic() -> u32 {
+    MAGIC ^ size_of::<CrashRecord>() as u32
+}
+
+/// Compute the CRC of a crash record.
+fn crc(record: &CrashRecord) -> u32 {
+    let bytes = (record as *const CrashRecord).cast::<u8>();
+    (0..size_of::<CrashRecord>()).fold(!0, |crc, idx| {
+        // Safety: in bounds of the record, which has no padding.
+        crc32_update(crc, unsafe { bytes.add(idx).read() })
+    }) ^ !0
+}
+
+/// Returns the crash record's storage, or `None` if the runtime builder didn't
+/// reserve a crash record.
+fn storage() -> Option<*mut Storage> {
+    unsafe extern "C" {
+        static mut __crash_record: Storage;
+    }
+    let size = symbol_value!("__crash_record_size");
+    (size != 0).then_some(&raw mut __crash_record)
+}
+
+/// Returns the crash record written before the last reset.
+///
+/// Returns `None` if there's no valid crash record, or if the runtime builder didn't
+/// reserve a crash record. The record stays valid until you call
+/// [`clear_crash_record`].
+pub fn crash_record() -> Option<CrashRecord> {
+    let storage = storage()?;
+    // Safety: the storage is reserved for the crash record. Only the HardFault
+    // handler writes a record, and it never returns.
+    let (magic_, crc_, record) = unsafe {
+        (
+            (&raw const (*storage).magic).read_volatile(),
+            (&raw const (*storage).crc).read_volatile(),
+            (&raw const (*storage).record).read_volatile(),
+        )
+    };
+    (magic_ == magic() && crc_ == crc(&record)).then_some(record)
+}
+
+/// Clear the crash record.
+///
+/// After this call, [`crash_record`] returns `None` until the next crash.
+pub fn clear_crash_record() {
+    if let Some(storage) = storage() {
+        // Safety: see crash_record.
+        unsafe { (&raw mut (*storage).magic).write_volatile(0) };
+    }
+}
+
+// The linker script selects this handler as `HardFault` when the runtime builder
+// reserves a crash record. Like the trampoline that `cortex-m-rt` generates for a
+// user's handler, it finds the exception frame using the `EXC_RETURN` value in lr,
+// then passes the frame in r0. The main stack may have overflowed, so the handler
+// moves MSP to its own stack before it calls into Rust. It never returns, so it
+// doesn't restore MSP. The handler shares the trampoline's section, so the branch
+// is in range.
+global_asm! {r#"
+.section .HardFault.imxrt_rt,"ax"
+.global __imxrt_rt_crash_hard_fault
+.type __imxrt_rt_crash_hard_fault,%function
+.thumb_func
+__imxrt_rt_crash_hard_fault:
+    mov r1, lr
+    tst r1, #4
+    ite eq
+    mrseq r0, MSP
+    mrsne r0, PSP
+    ldr r2, =__crash_stack
+    msr MSP, r2
+    b __imxrt_rt_save_crash_record
+.size __imxrt_rt_crash_hard_fault, . - __imxrt_rt_crash_hard_fault
+"#}
+
+/// Write the crash record, then reset.
+#[unsafe(no_mangle)]
+#[unsafe(link_section = ".HardFault.imxrt_rt")]
+unsafe extern "C" fn __imxrt_rt_save_crash_record(frame: *const u32, exc_return: u32) -> ! {
+    unsafe extern "C" {
+        static __sstack: u32;
+    }
+    const CFSR: *const u32 = 0xE000_ED28 as *const u32;
+    const HFSR: *const u32 = 0xE000_ED2C as *const u32;
+    const MMFAR: *const u32 = 0xE000_ED34 as *const u32;
+    const BFAR: *const u32 = 0xE000_ED38 as *const u32;
+    const AIRCR: *mut u32 = 0xE000_ED0C as *mut u32;
+
+    let sp = frame as u32;
+    let snippet = sp.wrapping_add(32);
+    let top = &raw const __sstack as u32;
+    // Only copy from the main stack, which has a known top.
+    let stack_len = if exc_return & 1 << 2 == 0 && snippet <= top {
+        ((top - snippet) / 4).min(CRASH_STACK_WORDS as u32)
+    } else {
+        0
+    };
+
+    // Safety: the exception frame is readable, since the processor just wrote it.
+    // The snippet is within the main stack. The fault registers are always
+    // readable.
+    let record = unsafe {
+        let mut record = CrashRecord {
+            frame: [0; 8],
+            sp,
+            exc_return,
+            cfsr: CFSR.read_volatile(),
+            hfsr: HFSR.read_volatile(),
+            mmfar: MMFAR.read_volatile(),
+            bfar: BFAR.read_volatile(),
+            stack_len,
+            stack: [0; CRASH_STACK_WORDS],
+        };
+        for (idx, word) in record.frame.iter_mut().enumerate() {
+            *word = frame.add(idx).read_volatile();
+        }
+        let snippet = snippet as *const u32;
+        for (idx, word) in record.stack[..stack_len as usize].iter_mut().enumerate() {
+            *word = snippet.add(idx).read_volatile();
+        }
+        record
+    };
+
+    if let Some(storage) = storage() {
+        // Safety: the storage is reserved for the crash record. Invalidate the
+        // record while it's written.
+        unsafe {
+            (&raw mut (*storage).magic).write_volatile(0);
+            (&raw mut (*storage).record).write_volatile(record);
+            (&raw mut (*storage).crc).write_volatile(crc(&record));
+            (&raw mut (*storage).magic).write_volatile(magic());
+        }
+    }
+
+    // Safety: requests a system reset. Nothing runs afterwards.
+    unsafe {
+        core::arch::asm!("dsb", options(nostack, preserves_flags));
+        AIRCR.write_volatile(0x05FA_0004);
+        core::arch::asm!("dsb", options(nostack, preserves_flags));
+    }
+    loop {
+        core::hint::spin_loop();
+    }
+}
@@ -0,0 +1,178 @@
+//! Copying deferred sections after `main()` starts.
+
+use core::{arch::global_asm, ffi::c_void};
+
+/// A section that the runtime may defer.
+///
+/// Select the deferred sections with the runtime builder. Then, use
+/// [`load_deferred`] to copy them.
+#[non_exhaustive]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Section {
+    /// Code.
+    Text,
+    /// Read-only data.
+    Rodata,
+    /// A section you defined with the runtime builder.
+    ///
+    /// This is either the name of an initialized fixed section, or the
+    /// pattern of an input placement. It must match the string given
+    /// to the runtime builder.
+    Custom(&'static str),
+}
+
+impl Section {
+    /// The key that identifies this section in the deferred table.
+    ///
+    /// Deferred instructions may call this before they're loaded, so it's always
+    /// inlined.
+    #[inline(always)]
+    const fn key(self) -> u32 {
+        match self {
+            Section::Text => const { key(".text") },
+            Section::Rodata => const { key(".rodata") },
+            Section::Custom(name) => key(name),
+        }
+    }
+}
+
+/// The 32-bit FNV-1a hash of `name`.
+///
+/// Keep this in sync with the runtime builder. Always inlined, like [`Section::key`].
+#[inline(always)]
+const fn key(name: &str) -> u32 {
+    let bytes = name.as_bytes();
+    let mut hash: u32 = 0x811C_9DC5;
+    let mut idx = 0;
+    while idx < bytes.len() {
+        hash = (hash ^ bytes[idx] as u32).wrapping_mul(0x0100_0193);
+        idx += 1;
+    }
+    hash
+}
+
+/// Describes a copy of a deferred section.
+///
+/// Use [`deferred_loads`] to find these. If you copy a section yourself, copy
+/// [`len()`](Self::len) bytes from [`source()`](Self::source) to
+/// [`destination()`](Self::destination). The source, destination, and length
+/// are 4-byte aligned.
+#[repr(C)]
+#[derive(Debug)]
+pub struct DeferredLoad {
+    key: u32,
+    start: *mut u32,
+    end: *mut u32,
+    load: *const u32,
+}
+
+// Safety: the table is immutable, and lives in the program image.
+unsafe impl Sync for DeferredLoad {}
+
+impl DeferredLoad {
+    /// Returns `true` if this copy belongs to `section`.
+    ///
+    /// A section may have more than one copy.
+    pub fn is_section(&self, section: Section) -> bool {
+        self.key == section.key()
+    }
+    /// The address of the section's contents in the program image.
+    pub fn source(&self) -> *const u32 {
+        self.load
+    }
+    /// The address where the section is used.
+    pub fn destination(&self) -> *mut u32 {
+        self.start
+    }
+    /// The size of the section, in bytes.
+    pub fn len(&self) -> usize {
+        self.end as usize - self.start as usize
+    }
+    /// Returns `true` if there's nothing to copy.
+    ///
+    /// This is the case for empty sections, and for sections that are
+    /// already at their destination.
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0 || self.start.cast_const() == self.load
+    }
+}
+
+/// Returns the copies of all deferred sections.
+pub fn deferred_loads() -> &'static [DeferredLoad] {
+    unsafe extern "C" {
+        static __sdeferred_table: c_void;
+        static __edeferred_table: c_void;
+    }
+    let start = (&raw const __sdeferred_table).cast::<DeferredLoad>();
+    let end = (&raw const __edeferred_table).cast::<DeferredLoad>();
+    // Safety: the linker script produces a table of DeferredLoad entries
+    // between these symbols. The table is in the program image.
+    unsafe { core::slice::from_raw_parts(start, end.offset_from(start) as usize) }
+}
+
+/// Copy a deferred section to its destination.
+///
+/// If `section` isn't deferred, this does nothing. After this call, you may use
+/// the contents of `section`.
+///
+/// The loader executes in place from flash, so you can call it before you load
+/// deferred instructions. The code that calls it must also be loaded; see the
+/// runtime builder's documentation.
+///
+/// # Safety
+///
+/// Nothing can be using the section's destination while this copies. If the
+/// destination is cached, you're responsible for cleaning the data cache and,
+/// for code, invalidating the instruction cache after the copy.
+#[inline(always)]
+pub unsafe fn load_deferred(section: Section) {
+    unsafe extern "C" {
+        fn __imxrt_rt_load_deferred(key: u32);
+    }
+    // Safety: the runtime builder ensures that the source and destination are valid
+    // for the length, and that they don't overlap. Caller ensures nothing uses the
+    // destination. The loader is in flash, usually out of branch range of the caller.
+    // Call it through a register, so the linker doesn't need a range extension thunk.
+    unsafe {
+        core::arch::asm!(
+            "blx {loader}",
+            loader = in(reg) __imxrt_rt_load_deferred as unsafe extern "C" fn(u32),
+            in("r0") section.key(),
+            clobber_abi("C"),
+        )
+    }
+}
+
+// Copy each entry of the deferred table that matches the key in r0. The loader
+// copies one word at a time, and it doesn't call any other function, so it works
+// before deferred instructions are loaded. The barriers ensure that the copy
+// completes before we fetch any instructions that it produced.
+global_asm! {r#"
+.section .xip.imxrt_rt_load_deferred,"ax"
+.global __imxrt_rt_load_deferred
+.type __imxrt_rt_load_deferred,%function
+.thumb_func
+__imxrt_rt_load_deferred:
+    push {{r4-r6, lr}}
+    ldr r1, =__sdeferred_table
+    ldr r2, =__edeferred_table
+    1:
+    cmp r1, r2
+    beq 3f
+    ldm r1!, {{r3-r6}}              @ r3 = key, r4 = dst, r5 = dst end, r6 = src.
+    cmp r3, r0
+    bne 1b
+    cmp r4, r6
+    beq 1b                          @ Already in place; copy nothing.
+    2:
+    cmp r4, r5
+    beq 1b
+    ldr r3, [r6], #4
+    str r3, [r4], #4
+    b 2b
+    3:
+    dsb
+    isb
+    pop {{r4-r6, pc}}
+.size __imxrt_rt_load_deferred, . - __imxrt_rt_load_deferred
+"#}
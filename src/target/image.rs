@@ -0,0 +1,260 @@
+//! The configuration that the runtime builder linked into the image.
+
+/// The chip family selected by the runtime builder.
+#[non_exhaustive]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Family {
+    /// i.MX RT 1010.
+    Imxrt1010,
+    /// i.MX RT 1015.
+    Imxrt1015,
+    /// i.MX RT 1020.
+    Imxrt1020,
+    /// i.MX RT 1040.
+    Imxrt1040,
+    /// i.MX RT 1050.
+    Imxrt1050,
+    /// i.MX RT 1060.
+    Imxrt1060,
+    /// i.MX RT 1064.
+    Imxrt1064,
+    /// i.MX RT 1160.
+    Imxrt1160,
+    /// i.MX RT 1170.
+    Imxrt1170,
+    /// i.MX RT 1180.
+    Imxrt1180,
+}
+
+impl Family {
+    /// Returns the family for the identifier that the runtime builder writes into
+    /// the image.
+    const fn from_id(id: u32) -> Option<Self> {
+        Some(match id {
+            0x1010 => Family::Imxrt1010,
+            0x1015 => Family::Imxrt1015,
+            0x1020 => Family::Imxrt1020,
+            0x1040 => Family::Imxrt1040,
+            0x1050 => Family::Imxrt1050,
+            0x1060 => Family::Imxrt1060,
+            0x1064 => Family::Imxrt1064,
+            0x1160 => Family::Imxrt1160,
+            0x1170 => Family::Imxrt1170,
+            0x1180 => Family::Imxrt1180,
+            _ => return None,
+        })
+    }
+
+    /// How many FlexRAM banks are available?
+    pub const fn flexram_bank_count(self) -> usize {
+        match self {
+            Family::Imxrt1010 | Family::Imxrt1015 => 4,
+            Family::Imxrt1020 => 8,
+            Family::Imxrt1040 | Family::Imxrt1050 | Family::Imxrt1060 | Family::Imxrt1064 => 16,
+            Family::Imxrt1160 | Family::Imxrt1170 => 16,
+            Family::Imxrt1180 => 2,
+        }
+    }
+}
+
+/// Returns the chip family that the image was linked for.
+pub fn family() -> Family {
+    Family::from_id(symbol_value!("__imxrt_rt_v0.2")).expect("Written by the runtime builder")
+}
+
+/// A memory selected by the runtime builder.
+#[non_exhaustive]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Memory {
+    /// External flash.
+    Flash,
+    /// Data tightly coupled memory (DTCM).
+    Dtcm,
+    /// Instruction tightly coupled memory (ITCM).
+    Itcm,
+    /// On-chip RAM (OCRAM).
+    Ocram,
+}
+
+impl Memory {
+    /// Decode a memory from the placements that the runtime builder writes into
+    /// the image.
+    const fn from_id(id: u32) -> Self {
+        match id & 0b11 {
+            0 => Memory::Flash,
+            1 => Memory::Itcm,
+            2 => Memory::Dtcm,
+            _ => Memory::Ocram,
+        }
+    }
+}
+
+/// The memories that the runtime builder selected for each section.
+#[non_exhaustive]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Placements {
+    /// Instructions.
+    pub text: Memory,
+    /// The instructions that don't fit into `text`.
+    ///
+    /// This is the same as `text` if instructions can't spill.
+    pub text_spill: Memory,
+    /// Read-only data.
+    pub rodata: Memory,
+    /// Mutable data.
+    pub data: Memory,
+    /// The vector table.
+    pub vectors: Memory,
+    /// Zero-initialized data.
+    pub bss: Memory,
+    /// Uninitialized data.
+    pub uninit: Memory,
+    /// Retained data.
+    pub retained: Memory,
+    /// The stack.
+    pub stack: Memory,
+    /// The heap.
+    pub heap: Memory,
+}
+
+/// Returns the section placements that the image was linked for.
+pub fn placements() -> Placements {
+    let placements = symbol_value!("__imxrt_rt_placements");
+    let memory = |idx: u32| Memory::from_id(placements >> (2 * idx));
+    Placements {
+        text: memory(0),
+        text_spill: memory(1),
+        rodata: memory(2),
+        data: memory(3),
+        vectors: memory(4),
+        bss: memory(5),
+        uninit: memory(6),
+        retained: memory(7),
+        stack: memory(8),
+        heap: memory(9),
+    }
+}
+
+/// Describes how a FlexRAM bank is used.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FlexRamKind {
+    /// The bank isn't used.
+    Unused,
+    /// The bank is OCRAM.
+    Ocram,
+    /// The bank is DTCM.
+    Dtcm,
+    /// The bank is ITCM.
+    Itcm,
+}
+
+/// The allocation of FlexRAM banks.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct FlexRamLayout {
+    family: Family,
+    config: u32,
+}
+
+impl FlexRamLayout {
+    /// The configuration value.
+    ///
+    /// On the 10xx, this is the value of IOMUXC_GPR17. On the 1160 and 1170, the
+    /// lower half is the value of IOMUXC_GPR17, and the upper half is the value
+    /// of IOMUXC_GPR18. On the 1180, this is the TCM_SIZE field of
+    /// the M33_CFG register in BLK_CTRL_S_AONMIX.
+    pub const fn config(&self) -> u32 {
+        self.config
+    }
+
+    /// Returns the allocation of each FlexRAM bank, starting with bank 0.
+    pub fn banks(&self) -> impl Iterator<Item = FlexRamKind> + use<> {
+        let (family, config) = (self.family, self.config);
+        (0..family.flexram_bank_count()).map(move |bank| {
+            if family == Family::Imxrt1180 {
+                return match (config, bank) {
+                    (0b10, _) | (0b00, 1) => FlexRamKind::Dtcm,
+                    _ => FlexRamKind::Itcm,
+                };
+            }
+            match (config >> (2 * bank)) & 0b11 {
+                0b00 => FlexRamKind::Unused,
+                0b01 => FlexRamKind::Ocram,
+                0b10 => FlexRamKind::Dtcm,
+                _ => FlexRamKind::Itcm,
+            }
+        })
+    }
+
+    /// Returns the number of banks allocated to `kind`.
+    pub fn count(&self, kind: FlexRamKind) -> usize {
+        self.banks().filter(|bank| *bank == kind).count()
+    }
+}
+
+/// Returns the FlexRAM layout that the image was linked for.
+///
+/// This is the layout that the runtime configures during pre-init. If the runtime
+/// builder used the fuse-defined layout, this is the layout described by the fuses.
+pub fn flexram_layout() -> FlexRamLayout {
+    FlexRamLayout {
+        family: family(),
+        config: symbol_value!("__flexram_config"),
+    }
+}
+
+/// Returns the FlexRAM layout configured in the IOMUXC general purpose registers.
+///
+/// Returns `None` if IOMUXC_GPR16 selects the fuse-defined layout, since the other
+/// registers don't describe that layout. Also returns `None` on the 1180.
+pub fn configured_flexram_layout() -> Option<FlexRamLayout> {
+    let family = family();
+    let gpr: *const u32 = match family {
+        Family::Imxrt1010
+        | Family::Imxrt1015
+        | Family::Imxrt1020
+        | Family::Imxrt1040
+        | Family::Imxrt1050
+        | Family::Imxrt1060
+        | Family::Imxrt1064 => 0x400A_C000 as _,
+        Family::Imxrt1160 | Family::Imxrt1170 => 0x400E_4000 as _,
+        Family::Imxrt1180 => return None,
+    };
+    // Safety: the IOMUXC_GPR registers are always readable. Reads have no side effects.
+    let [gpr16, gpr17, gpr18] = unsafe { [16, 17, 18].map(|idx| gpr.add(idx).read_volatile()) };
+    if gpr16 & 1 << 2 == 0 {
+        return None;
+    }
+    let config = if family.flexram_bank_count() < 16 {
+        gpr17 & !(u32::MAX << (2 * family.flexram_bank_count()))
+    } else if matches!(family, Family::Imxrt1160 | Family::Imxrt1170) {
+        (gpr17 & 0xFFFF) | gpr18 << 16
+    } else {
+        gpr17
+    };
+    Some(FlexRamLayout { family, config })
+}
+
+/// The hardware's FlexRAM layout can't be verified, or it doesn't match the linked layout.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum VerifyFlexRamError {
+    /// The configured layout is different from [`flexram_layout`].
+    Mismatch(FlexRamLayout),
+    /// The registers don't describe the layout, so it can't be verified.
+    ///
+    /// This happens when the fuses define the layout, and on the 1180. See
+    /// [`configured_flexram_layout`].
+    Unverifiable,
+}
+
+/// Checks that the hardware's FlexRAM layout matches the linked layout.
+///
+/// Returns [`VerifyFlexRamError::Mismatch`] with the configured layout if it's
+/// different from [`flexram_layout`]. Returns [`VerifyFlexRamError::Unverifiable`]
+/// if the registers don't describe the layout.
+pub fn verify_flexram_layout() -> Result<(), VerifyFlexRamError> {
+    match configured_flexram_layout() {
+        Some(configured) if configured == flexram_layout() => Ok(()),
+        Some(configured) => Err(VerifyFlexRamError::Mismatch(configured)),
+        None => Err(VerifyFlexRamError::Unverifiable),
+    }
+}
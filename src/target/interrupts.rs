@@ -0,0 +1,93 @@
+//! Interrupt handlers installed at runtime.
+
+use core::arch::asm;
+
+/// An entry in the vector table.
+pub type InterruptHandler = unsafe extern "C" fn();
+
+/// The interrupt number is outside of the vector table.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct InvalidInterrupt(pub u16);
+
+/// Returns the runtime's vector table, and the number of device interrupts in the table.
+fn vector_table() -> (*mut InterruptHandler, usize) {
+    unsafe extern "C" {
+        static mut __svector_table: InterruptHandler;
+        static __evector_table: InterruptHandler;
+        static __evector_table_reserved: InterruptHandler;
+    }
+    let start = &raw mut __svector_table;
+    let end =
+        (&raw const __evector_table as usize).max(&raw const __evector_table_reserved as usize);
+    (start, (end - start as usize) / 4 - 16)
+}
+
+/// Returns the number of device interrupts in the runtime's vector table.
+///
+/// This is the number of interrupts in the device's `__INTERRUPTS` table, or the
+/// number reserved by the runtime builder, whichever is larger.
+pub fn interrupt_count() -> usize {
+    vector_table().1
+}
+
+/// Replace the vector table entry for `irqn`, returning the previous entry.
+fn swap(irqn: u16, handler: InterruptHandler) -> Result<InterruptHandler, InvalidInterrupt> {
+    let (table, count) = vector_table();
+    if usize::from(irqn) >= count {
+        return Err(InvalidInterrupt(irqn));
+    }
+    // Masking interrupts prevents another registration from interleaving. The barriers
+    // make sure that the next exception entry observes the new handler.
+    Ok(interrupt_free(|| {
+        // Safety: the entry is within the vector table.
+        unsafe {
+            let entry = table.add(16 + usize::from(irqn));
+            let previous = entry.read_volatile();
+            entry.write_volatile(handler);
+            asm!("dsb", "isb", options(nostack, preserves_flags));
+            previous
+        }
+    }))
+}
+
+/// Run `func` with interrupts masked.
+pub(super) fn interrupt_free<R>(func: impl FnOnce() -> R) -> R {
+    let primask: u32;
+    // Safety: masking interrupts doesn't affect memory safety.
+    unsafe {
+        asm!("mrs {}, PRIMASK", "cpsid i", out(reg) primask, options(nostack, preserves_flags))
+    };
+    let result = func();
+    if primask & 1 == 0 {
+        // Safety: interrupts were enabled before the call.
+        unsafe { asm!("cpsie i", options(nostack, preserves_flags)) };
+    }
+    result
+}
+
+/// Install a handler for device interrupt `irqn`, returning the previous handler.
+///
+/// The handler runs the next time that the interrupt is taken, including an interrupt
+/// that's already pending. This doesn't enable the interrupt in the NVIC.
+///
+/// The runtime copies the vector table into RAM, and `cortex-m-rt` points VTOR at that
+/// table. If you point VTOR at another table, this function still modifies the
+/// runtime's table.
+///
+/// Returns an error if `irqn` isn't in the vector table. See [`interrupt_count`].
+pub fn register_interrupt(
+    irqn: u16,
+    handler: extern "C" fn(),
+) -> Result<InterruptHandler, InvalidInterrupt> {
+    swap(irqn, handler)
+}
+
+/// Point device interrupt `irqn` at `DefaultHandler`, returning the previous handler.
+///
+/// Returns an error if `irqn` isn't in the vector table. See [`interrupt_count`].
+pub fn unregister_interrupt(irqn: u16) -> Result<InterruptHandler, InvalidInterrupt> {
+    unsafe extern "C" {
+        fn DefaultHandler();
+    }
+    swap(irqn, DefaultHandler)
+}
@@ -0,0 +1,135 @@
+//! The reason for the last reset.
+
+use super::image::{Family, family};
+
+/// Why the chip reset.
+///
+/// See [`reset_reason`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum ResetReason {
+    /// The chip powered on.
+    PowerOn,
+    /// Software requested a reset with `SCB_AIRCR[SYSRESETREQ]`.
+    ///
+    /// On the 10xx, this also indicates a lockup.
+    Software,
+    /// The core locked up.
+    ///
+    /// Only the 1160, 1170, and 1180 distinguish a lockup from a software reset.
+    Lockup,
+    /// A watchdog expired.
+    Watchdog,
+    /// The reset pin was asserted.
+    Pin,
+    /// A debugger reset the chip through JTAG.
+    Debugger,
+    /// The temperature sensor detected a high temperature.
+    TemperatureSensor,
+    /// The Central Security Unit (CSU) requested a reset.
+    ///
+    /// On the 1180, the EdgeLock secure enclave requested a reset.
+    Security,
+    /// The reset status doesn't indicate a known reason.
+    Unknown,
+}
+
+/// SRSR flags on the 10xx, in the order that they're reported.
+const REASONS_10XX: &[(u32, ResetReason)] = &[
+    (1 << 0, ResetReason::PowerOn),
+    (1 << 8, ResetReason::TemperatureSensor),
+    (1 << 4 | 1 << 7, ResetReason::Watchdog),
+    (1 << 1, ResetReason::Software),
+    (1 << 3, ResetReason::Pin),
+    (1 << 5 | 1 << 6, ResetReason::Debugger),
+    (1 << 2, ResetReason::Security),
+];
+
+/// SRSR flags on the 1160 and 1170, in the order that they're reported.
+const REASONS_11XX: &[(u32, ResetReason)] = &[
+    (1 << 0, ResetReason::PowerOn),
+    (1 << 10, ResetReason::TemperatureSensor),
+    (1 << 5 | 1 << 8 | 1 << 9, ResetReason::Watchdog),
+    (1 << 2, ResetReason::Lockup),
+    (1 << 1, ResetReason::Software),
+    (1 << 4, ResetReason::Pin),
+    (1 << 6 | 1 << 7, ResetReason::Debugger),
+    (1 << 3, ResetReason::Security),
+];
+
+/// SRSR flags on the 1180, in the order that they're reported.
+///
+/// The DCDC overvoltage and EtherCAT flags are `Unknown`.
+const REASONS_1180: &[(u32, ResetReason)] = &[
+    (1 << 0, ResetReason::PowerOn),
+    (1 << 6, ResetReason::TemperatureSensor),
+    (0b11111 << 1, ResetReason::Watchdog),
+    (1 << 10 | 1 << 12, ResetReason::Lockup),
+    (1 << 9 | 1 << 11, ResetReason::Software),
+    (1 << 16, ResetReason::Pin),
+    (1 << 8, ResetReason::Debugger),
+    (1 << 7, ResetReason::Security),
+];
+
+/// Returns the reset status captured during pre-init.
+///
+/// This is the value of the SRC reset status register (SRSR) when the chip booted.
+/// The flags accumulate across resets until something clears them; see
+/// [`clear_reset_status`]. Returns `None` if the runtime builder didn't enable
+/// the capture.
+pub fn reset_status() -> Option<u32> {
+    unsafe extern "C" {
+        static __reset_reason: u32;
+    }
+    let size = symbol_value!("__reset_reason_size");
+    if size == 0 {
+        return None;
+    }
+    // Safety: the runtime writes this record before main, and nothing
+    // else should write it.
+    Some(unsafe { (&raw const __reset_reason).read_volatile() })
+}
+
+/// Clear the reset status flags captured during pre-init.
+///
+/// This clears the SRC reset status register (SRSR) flags that [`reset_status`]
+/// reports, so that the next boot observes only the resets that happen after this
+/// call. On the 1160, 1170, and 1180, this includes the flags of the other core. Does nothing
+/// if the runtime builder didn't enable the capture.
+pub fn clear_reset_status() {
+    let Some(status) = reset_status() else {
+        return;
+    };
+    let srsr = symbol_value!("__src_srsr");
+    // Safety: the linker script provides the SRSR address for this chip when the
+    // capture is enabled. The flags are write-one-to-clear.
+    unsafe { (srsr as *mut u32).write_volatile(status) };
+}
+
+/// Returns the reason for the last reset.
+///
+/// If the reset status indicates more than one reason, this returns the first
+/// reason in this order: power on, temperature sensor, watchdog, lockup,
+/// software, pin, debugger, and security. Use [`reset_status`] to inspect
+/// all flags.
+///
+/// Returns `None` if the runtime builder didn't enable the capture.
+pub fn reset_reason() -> Option<ResetReason> {
+    let status = reset_status()?;
+    let reasons = match family() {
+        Family::Imxrt1160 | Family::Imxrt1170 => REASONS_11XX,
+        Family::Imxrt1180 => REASONS_1180,
+        Family::Imxrt1010
+        | Family::Imxrt1015
+        | Family::Imxrt1020
+        | Family::Imxrt1040
+        | Family::Imxrt1050
+        | Family::Imxrt1060
+        | Family::Imxrt1064 => REASONS_10XX,
+    };
+    let reason = reasons
+        .iter()
+        .find(|(mask, _)| status & mask != 0)
+        .map_or(ResetReason::Unknown, |(_, reason)| *reason);
+    Some(reason)
+}
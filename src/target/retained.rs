@@ -0,0 +1,191 @@
+//! Memory that's retained across warm resets.
+
+use core::{cell::UnsafeCell, mem::MaybeUninit};
+
+/// Marks a valid header. Mixed with the size of the retained value.
+const MAGIC: u32 = 0x5245_5441;
+
+/// Describes what happened to retained memory across a reset.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Retention {
+    /// The contents survived the reset.
+    ///
+    /// This is typical after a warm reset, like a watchdog or software reset.
+    Survived,
+    /// The contents did not survive the reset, and they were re-initialized.
+    ///
+    /// This is typical after a power-on reset. It also happens if the previous
+    /// program was reset while it was updating the contents.
+    Lost,
+}
+
+/// A value that's retained across warm resets.
+///
+/// Place a `Retained` in the `.retained` section. The runtime never initializes
+/// that section. A header, containing a marker and a CRC of the value, lets the
+/// runtime detect if the value survived the reset.
+///
+/// ```no_run
+/// use imxrt_rt::{Retained, Retention};
+///
+/// #[unsafe(link_section = ".retained")]
+/// static RESETS: Retained<u32> = Retained::new();
+///
+/// // Safety: called once, before anything else uses RESETS.
+/// let (mut resets, retention) = unsafe { RESETS.acquire(|| 0) };
+/// if retention == Retention::Survived {
+///     resets.update(|count| *count += 1);
+/// }
+/// ```
+///
+/// `T` should be plain data, without pointers or references. Prefer types without
+/// padding bytes. If you change `T` between programs, the runtime may not detect
+/// the change unless the size of `T` changes.
+#[repr(C)]
+pub struct Retained<T> {
+    magic: UnsafeCell<u32>,
+    crc: UnsafeCell<u32>,
+    value: UnsafeCell<MaybeUninit<T>>,
+}
+
+// Safety: access to the value requires the one-and-only RetainedRef.
+unsafe impl<T: Send> Sync for Retained<T> {}
+
+impl<T: Copy> Retained<T> {
+    /// Create retained memory.
+    ///
+    /// Since the `.retained` section isn't initialized, the program image
+    /// doesn't include this value.
+    pub const fn new() -> Self {
+        Self {
+            magic: UnsafeCell::new(0),
+            crc: UnsafeCell::new(0),
+            value: UnsafeCell::new(MaybeUninit::uninit()),
+        }
+    }
+
+    /// Acquire the retained value.
+    ///
+    /// If the value survived the reset, the return indicates [`Retention::Survived`].
+    /// Otherwise, the implementation initializes the value with `init`, and the return
+    /// indicates [`Retention::Lost`].
+    ///
+    /// # Safety
+    ///
+    /// Call this at most once per reset. Otherwise, you'll have two references to the
+    /// same mutable memory.
+    pub unsafe fn acquire(&'static self, init: impl FnOnce() -> T) -> (RetainedRef<T>, Retention) {
+        let mut retained = RetainedRef { retained: self };
+        // Safety: caller guarantees that we're the only accessor.
+        let valid = unsafe { *self.magic.get() == magic::<T>() && *self.crc.get() == self.crc() };
+        if valid {
+            (retained, Retention::Survived)
+        } else {
+            retained.set(init());
+            (retained, Retention::Lost)
+        }
+    }
+
+    /// Compute the CRC of the value.
+    fn crc(&self) -> u32 {
+        let bytes = self.value.get().cast::<MaybeUninit<u8>>();
+        (0..size_of::<T>()).fold(!0, |crc, idx| {
+            // Safety: in bounds of the value.
+            crc32_update(crc, unsafe { read_byte(bytes.add(idx)) })
+        }) ^ !0
+    }
+}
+
+impl<T: Copy> Default for Retained<T> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Read a byte that may be uninitialized, like a padding byte, or a byte that nothing
+/// wrote since power on.
+///
+/// Reading an uninitialized byte as a `u8` is undefined behavior, so the load happens
+/// in assembly. The compiler can't see the load, and it treats the output like any
+/// other initialized value. The hardware returns whatever the RAM holds.
+///
+/// # Safety
+///
+/// `byte` must point into a live value.
+unsafe fn read_byte(byte: *const MaybeUninit<u8>) -> u8 {
+    let value: u8;
+    // Safety: the caller's pointer is valid for reads, and a byte load has no
+    // alignment requirement.
+    unsafe {
+        core::arch::asm!(
+            "ldrb {0}, [{1}]",
+            out(reg) value,
+            in(reg) byte,
+            options(nostack, preserves_flags, readonly),
+        )
+    };
+    value
+}
+
+/// The marker for a valid `Retained<T>`.
+const fn magic<T>() -> u32 {
+    MAGIC ^ size_of::<T>() as u32
+}
+
+/// Update a (reflected) CRC-32 with another byte.
+pub(crate) const fn crc32_update(mut crc: u32, byte: u8) -> u32 {
+    crc ^= byte as u32;
+    let mut bit = 0;
+    while bit < 8 {
+        crc = if crc & 1 != 0 {
+            (crc >> 1) ^ 0xEDB8_8320
+        } else {
+            crc >> 1
+        };
+        bit += 1;
+    }
+    crc
+}
+
+/// Access to a retained value.
+///
+/// Use [`Retained::acquire`] to get access. Every modification updates
+/// the header. If a reset interrupts a modification, the next program
+/// observes that the value was [`Lost`](Retention::Lost).
+pub struct RetainedRef<T: 'static> {
+    retained: &'static Retained<T>,
+}
+
+impl<T: Copy> RetainedRef<T> {
+    /// Returns a copy of the value.
+    pub fn get(&self) -> T {
+        // Safety: the value is always initialized once we have a RetainedRef.
+        unsafe { (*self.retained.value.get()).assume_init() }
+    }
+
+    /// Replace the value.
+    pub fn set(&mut self, value: T) {
+        self.update(|retained| *retained = value);
+    }
+
+    /// Modify the value in place.
+    pub fn update<R>(&mut self, func: impl FnOnce(&mut T) -> R) -> R {
+        // Safety: we're the only accessor. Invalidate the header so that a reset
+        // during the modification is detected.
+        unsafe {
+            *self.retained.magic.get() = 0;
+            let result = func((*self.retained.value.get()).assume_init_mut());
+            *self.retained.crc.get() = self.retained.crc();
+            *self.retained.magic.get() = magic::<T>();
+            result
+        }
+    }
+
+    /// Invalidate the value.
+    ///
+    /// After the next reset, the value will be [`Lost`](Retention::Lost).
+    pub fn invalidate(&mut self) {
+        // Safety: we're the only accessor.
+        unsafe { *self.retained.magic.get() = 0 };
+    }
+}
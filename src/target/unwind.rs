@@ -0,0 +1,378 @@
+//! Stack backtraces from the ARM exception-handling tables.
+//!
+//! The unwinder interprets the unwind instructions described by the "Exception
+//! Handling ABI for the Arm Architecture" (EHABI). It never calls personality
+//! routines, and it ignores the values of floating-point registers.
+
+use core::{arch::global_asm, ffi::c_void};
+
+/// The program counter's index in the register set.
+const PC: usize = 15;
+/// The link register's index in the register set.
+const LR: usize = 14;
+/// The stack pointer's index in the register set.
+const SP: usize = 13;
+
+/// An `.ARM.exidx` entry value that marks a function without unwind information.
+const EXIDX_CANTUNWIND: u32 = 1;
+
+/// A frame in a [`Backtrace`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Frame {
+    pc: u32,
+    sp: u32,
+}
+
+impl Frame {
+    /// The frame's program counter.
+    ///
+    /// For the first frame, and for a frame interrupted by an exception, this is
+    /// the address of the next instruction to execute. For the other frames, this
+    /// is the return address of a call. The Thumb bit is cleared.
+    pub fn pc(&self) -> u32 {
+        self.pc
+    }
+    /// The frame's stack pointer.
+    pub fn sp(&self) -> u32 {
+        self.sp
+    }
+}
+
+/// Walks the stack, one frame at a time.
+///
+/// Use [`backtrace`] to create this iterator. The iterator ends when it reaches a
+/// function without unwind information, an invalid stack pointer, or an exception
+/// frame on the process stack.
+#[derive(Debug, Clone)]
+pub struct Backtrace {
+    regs: [u32; 16],
+    /// Set if `regs[PC]` is a return address, instead of the address of the next
+    /// instruction.
+    returned: bool,
+    done: bool,
+}
+
+/// Returns a backtrace that starts at the caller.
+///
+/// The unwinder uses the unwind tables kept by the runtime builder. If the builder
+/// didn't keep the tables, the backtrace only has the caller's frame.
+///
+/// The backtrace crosses exception frames on the main stack. If you call this from
+/// an exception handler, like `HardFault`, the backtrace continues into the code
+/// that was interrupted.
+///
+/// ```ignore
+/// use imxrt_rt::{ExceptionFrame, exception};
+///
+/// #[exception]
+/// unsafe fn HardFault(_: &ExceptionFrame) -> ! {
+///     for frame in imxrt_rt::backtrace().take(32) {
+///         // Record frame.pc()...
+///     }
+///     loop {}
+/// }
+/// ```
+#[inline(always)]
+pub fn backtrace() -> Backtrace {
+    unsafe extern "C" {
+        fn __imxrt_rt_capture_registers(regs: *mut [u32; 16]);
+    }
+    let mut regs = [0; 16];
+    // Safety: the function only writes the registers into the array.
+    unsafe { __imxrt_rt_capture_registers(&mut regs) };
+    Backtrace {
+        regs,
+        returned: true,
+        done: false,
+    }
+}
+
+impl Iterator for Backtrace {
+    type Item = Frame;
+    fn next(&mut self) -> Option<Frame> {
+        if self.done {
+            return None;
+        }
+        let frame = Frame {
+            pc: self.regs[PC] & !1,
+            sp: self.regs[SP],
+        };
+        self.done = self.step().is_none();
+        Some(frame)
+    }
+}
+
+impl Backtrace {
+    /// Unwind one frame. Returns `None` if there are no more frames.
+    fn step(&mut self) -> Option<()> {
+        let (pc, sp) = (self.regs[PC], self.regs[SP]);
+        // A return address follows the call. Look up the call.
+        let address = if self.returned {
+            (pc & !1).wrapping_sub(2)
+        } else {
+            pc & !1
+        };
+        let instructions = find_instructions(address)?;
+        self.execute(instructions)?;
+        self.returned = true;
+        if is_exc_return(self.regs[PC]) {
+            self.unstack_exception()?;
+        }
+        let (new_pc, new_sp) = (self.regs[PC], self.regs[SP]);
+        if new_pc == 0 || new_sp < sp || (new_sp == sp && new_pc == pc) {
+            return None;
+        }
+        Some(())
+    }
+
+    /// Execute the unwind instructions, restoring the caller's registers.
+    fn execute(&mut self, mut instructions: Instructions) -> Option<()> {
+        let mut vsp = self.regs[SP];
+        let mut pc_restored = false;
+        while let Some(op) = instructions.next_byte() {
+            match op {
+                0x00..=0x3F => vsp = vsp.wrapping_add((u32::from(op & 0x3F) << 2) + 4),
+                0x40..=0x7F => vsp = vsp.wrapping_sub((u32::from(op & 0x3F) << 2) + 4),
+                0x80..=0x8F => {
+                    let mask = u16::from(op & 0xF) << 8 | u16::from(instructions.next_byte()?);
+                    if mask == 0 {
+                        return None; // Refuse to unwind.
+                    }
+                    vsp = self.pop(vsp, mask << 4)?;
+                    pc_restored |= mask & (1 << 11) != 0;
+                }
+                0x90..=0x9F => {
+                    let reg = usize::from(op & 0xF);
+                    if reg == SP || reg == PC {
+                        return None;
+                    }
+                    vsp = self.regs[reg];
+                }
+                0xA0..=0xAF => {
+                    let count = op & 0x7;
+                    let mut mask = (1u16 << (count + 1)) - 1;
+                    if op & 0x8 != 0 {
+                        mask |= 1 << (LR - 4);
+                    }
+                    vsp = self.pop(vsp, mask << 4)?;
+                }
+                0xB0 => break,
+                0xB1 => {
+                    let mask = instructions.next_byte()?;
+                    if mask == 0 || mask & 0xF0 != 0 {
+                        return None;
+                    }
+                    vsp = self.pop(vsp, u16::from(mask))?;
+                }
+                0xB2 => {
+                    let mut offset = 0u32;
+                    let mut shift = 0;
+                    loop {
+                        let byte = instructions.next_byte()?;
+                        offset |= u32::from(byte & 0x7F).checked_shl(shift)?;
+                        shift += 7;
+                        if byte & 0x80 == 0 {
+                            break;
+                        }
+                    }
+                    vsp = vsp.wrapping_add(0x204).wrapping_add(offset << 2);
+                }
+                // Floating-point registers. Skip them.
+                0xB3 => {
+                    let count = u32::from(instructions.next_byte()? & 0xF) + 1;
+                    vsp = vsp.wrapping_add(count * 8 + 4);
+                }
+                0xB8..=0xBF => vsp = vsp.wrapping_add((u32::from(op & 0x7) + 1) * 8 + 4),
+                0xC8 | 0xC9 => {
+                    let count = u32::from(instructions.next_byte()? & 0xF) + 1;
+                    vsp = vsp.wrapping_add(count * 8);
+                }
+                0xD0..=0xD7 => vsp = vsp.wrapping_add((u32::from(op & 0x7) + 1) * 8),
+                _ => return None,
+            }
+        }
+        if !pc_restored {
+            self.regs[PC] = self.regs[LR];
+        }
+        self.regs[SP] = vsp;
+        Some(())
+    }
+
+    /// Pop the registers in `mask` from `vsp`, lowest register first. Returns
+    /// the new `vsp`.
+    fn pop(&mut self, mut vsp: u32, mask: u16) -> Option<u32> {
+        for reg in 0..16 {
+            if mask & (1 << reg) != 0 {
+                self.regs[reg] = load(vsp)?;
+                vsp = vsp.wrapping_add(4);
+            }
+        }
+        if mask & (1 << SP) != 0 {
+            vsp = self.regs[SP];
+        }
+        Some(vsp)
+    }
+
+    /// Restore the registers saved by the hardware when it entered an exception.
+    fn unstack_exception(&mut self) -> Option<()> {
+        let exc_return = self.regs[PC];
+        if exc_return & (1 << 2) != 0 {
+            return None; // On the process stack.
+        }
+        let mut frame = self.regs[SP];
+        if exc_return & (1 << 5) == 0 {
+            frame = frame.wrapping_add(0x28); // Additional state context (ARMv8-M).
+        }
+        for (offset, reg) in [0, 1, 2, 3, 12, LR, PC].into_iter().enumerate() {
+            self.regs[reg] = load(frame.wrapping_add(offset as u32 * 4))?;
+        }
+        let xpsr = load(frame.wrapping_add(7 * 4))?;
+        let mut sp = frame.wrapping_add(0x20);
+        if exc_return & (1 << 4) == 0 {
+            sp = sp.wrapping_add(0x48); // Floating-point context.
+        }
+        if xpsr & (1 << 9) != 0 {
+            sp = sp.wrapping_add(4); // Stack alignment padding.
+        }
+        self.regs[SP] = sp;
+        self.returned = false;
+        Some(())
+    }
+}
+
+/// Returns `true` if `pc` is an `EXC_RETURN` value.
+fn is_exc_return(pc: u32) -> bool {
+    pc & 0xFF00_0000 == 0xFF00_0000
+}
+
+/// Load a word from the main stack.
+///
+/// Returns `None` if the address isn't aligned, or if it's outside of the stack.
+fn load(address: u32) -> Option<u32> {
+    unsafe extern "C" {
+        static __estack: c_void;
+        static __sstack: c_void;
+    }
+    let bottom = &raw const __estack as u32;
+    let top = &raw const __sstack as u32;
+    if !address.is_multiple_of(4) || address < bottom || address >= top {
+        return None;
+    }
+    // Safety: the address is aligned, and it's within the stack.
+    Some(unsafe { (address as *const u32).read_volatile() })
+}
+
+/// Decode a 31-bit offset relative to `place`.
+fn prel31(place: *const u32) -> u32 {
+    // Safety: the caller's pointer is within an unwind table.
+    let offset = ((unsafe { place.read() } << 1) as i32) >> 1;
+    (place as u32).wrapping_add(offset as u32)
+}
+
+/// Find the unwind instructions for the function containing `address`.
+fn find_instructions(address: u32) -> Option<Instructions> {
+    // The symbols are zero when the runtime builder discards the tables. Rust
+    // assumes that a static's address is never zero, so read them with assembly.
+    let start = symbol_value!("__exidx_start") as *const [u32; 2];
+    let end = symbol_value!("__exidx_end") as *const [u32; 2];
+    if start == end {
+        return None;
+    }
+    // Safety: the linker script places a table of index entries between these
+    // symbols. The linker sorts the entries by function address.
+    let entries = unsafe { core::slice::from_raw_parts(start, end.offset_from(start) as usize) };
+    let idx = entries.partition_point(|entry| prel31(&entry[0]) <= address);
+    let entry = entries.get(idx.checked_sub(1)?)?;
+    let data = entry[1];
+    if data == EXIDX_CANTUNWIND {
+        return None;
+    }
+    if data & (1 << 31) != 0 {
+        // Compact model, with the instructions in the index.
+        return (data >> 24 == 0x80).then(|| Instructions::new(data, 3, core::ptr::null(), 0));
+    }
+    let table = prel31(&entry[1]) as *const u32;
+    // Safety: the index entry points into the exception table.
+    let header = unsafe { table.read() };
+    // Safety: the header describes how many words follow.
+    unsafe {
+        if header & (1 << 31) == 0 {
+            // Generic model. The instructions follow the personality routine's address.
+            let header = table.add(1).read();
+            Some(Instructions::new(
+                header,
+                3,
+                table.add(2),
+                (header >> 24) as usize,
+            ))
+        } else {
+            match (header >> 24) & 0xF {
+                0 => Some(Instructions::new(header, 3, core::ptr::null(), 0)),
+                1 | 2 => Some(Instructions::new(
+                    header,
+                    2,
+                    table.add(1),
+                    ((header >> 16) & 0xFF) as usize,
+                )),
+                _ => None,
+            }
+        }
+    }
+}
+
+/// Unwind instructions, stored most-significant byte first.
+struct Instructions {
+    word: u32,
+    /// The bytes in `word` that are yet to be read.
+    bytes: u32,
+    next: *const u32,
+    /// The words, starting at `next`, that are yet to be read.
+    words: usize,
+}
+
+impl Instructions {
+    /// The last `bytes` bytes of `word` are instructions. Then, `words` words of
+    /// instructions follow at `next`.
+    fn new(word: u32, bytes: u32, next: *const u32, words: usize) -> Self {
+        Self {
+            word,
+            bytes,
+            next,
+            words,
+        }
+    }
+
+    /// Returns the next instruction byte, or `None` if there are no more.
+    fn next_byte(&mut self) -> Option<u8> {
+        if self.bytes == 0 {
+            if self.words == 0 {
+                return None;
+            }
+            // Safety: the exception table has this many words of instructions.
+            unsafe {
+                self.word = self.next.read();
+                self.next = self.next.add(1);
+            }
+            self.words -= 1;
+            self.bytes = 4;
+        }
+        self.bytes -= 1;
+        Some((self.word >> (8 * self.bytes)) as u8)
+    }
+}
+
+global_asm! {r#"
+@ Capture the caller's registers for a backtrace. The first frame is the caller,
+@ at the return address. r0 points to 16 words.
+.section .text.__imxrt_rt_capture_registers,"ax"
+.global __imxrt_rt_capture_registers
+.type __imxrt_rt_capture_registers,%function
+.thumb_func
+__imxrt_rt_capture_registers:
+    stm r0, {{r0-r12}}
+    str sp, [r0, #52]
+    str lr, [r0, #56]
+    str lr, [r0, #60]
+    bx lr
+.size __imxrt_rt_capture_registers, . - __imxrt_rt_capture_registers
+"#}
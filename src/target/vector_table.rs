@@ -0,0 +1,164 @@
+//! Relocating the active vector table.
+
+use core::{arch::asm, cell::UnsafeCell};
+
+use super::interrupts::{interrupt_count, interrupt_free};
+
+/// The Vector Table Offset Register.
+const VTOR: *mut u32 = 0xE000_ED08 as *mut u32;
+
+/// Memory for a relocated vector table with `N` entries.
+///
+/// The table has the 1024 byte alignment that the runtime builder uses for the vector
+/// table. Place it in RAM. To hold the runtime's vector table, `N` must be at least
+/// 16 plus [`interrupt_count`](crate::interrupt_count).
+///
+/// ```no_run
+/// use imxrt_rt::VectorTable;
+///
+/// static VECTORS: VectorTable<256> = VectorTable::new();
+/// imxrt_rt::relocate_vector_table(&VECTORS).unwrap();
+/// ```
+#[repr(C, align(1024))]
+pub struct VectorTable<const N: usize>(UnsafeCell<[u32; N]>);
+
+// Safety: the runtime only accesses the table with interrupts masked.
+unsafe impl<const N: usize> Sync for VectorTable<N> {}
+
+impl<const N: usize> VectorTable<N> {
+    /// Create memory for a vector table.
+    pub const fn new() -> Self {
+        Self(UnsafeCell::new([0; N]))
+    }
+
+    /// Returns the address of the table.
+    pub const fn as_ptr(&self) -> *const u32 {
+        self.0.get().cast()
+    }
+}
+
+impl<const N: usize> Default for VectorTable<N> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// The vector table can't be relocated.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RelocateError {
+    /// The destination is smaller than the runtime's vector table.
+    TooSmall,
+    /// The destination isn't aligned for its size.
+    ///
+    /// VTOR requires 1024 byte alignment. Tables larger than 1024 bytes must be aligned
+    /// to their size, rounded up to a power of two.
+    Misaligned,
+    /// The runtime builder didn't reserve a relocated vector table.
+    NotReserved,
+}
+
+/// Returns the address of the active vector table, as indicated by VTOR.
+pub fn active_vector_table() -> *const u32 {
+    // Safety: VTOR is always readable.
+    unsafe { VTOR.read_volatile() as *const u32 }
+}
+
+/// Returns the address of the runtime's vector table.
+///
+/// This is the RAM table that `cortex-m-rt` activates when it boots.
+pub fn runtime_vector_table() -> *const u32 {
+    unsafe extern "C" {
+        static __svector_table: u32;
+    }
+    &raw const __svector_table
+}
+
+/// Copy the active vector table into `table`, then point VTOR at `table`.
+///
+/// The copy has as many entries as the runtime's vector table. Interrupts are masked
+/// while the table is copied and activated. Subsequent exceptions use `table`.
+/// [`register_interrupt`](crate::register_interrupt) still modifies the runtime's
+/// vector table.
+///
+/// Returns an error if `table` is too small, or if it isn't aligned for its size.
+pub fn relocate_vector_table<const N: usize>(
+    table: &'static VectorTable<N>,
+) -> Result<(), RelocateError> {
+    // Safety: the static table is only accessed with interrupts masked.
+    unsafe { relocate(table.0.get().cast(), N) }
+}
+
+/// Copy the active vector table into the builder's reservation, then point VTOR at the
+/// reservation.
+///
+/// See [`relocate_vector_table`] for more information. Returns an error if the
+/// runtime builder didn't reserve a relocated vector table.
+pub fn relocate_vector_table_to_reserved() -> Result<(), RelocateError> {
+    unsafe extern "C" {
+        static mut __svector_table_relocated: u32;
+        static __evector_table_relocated: u32;
+    }
+    let start = &raw mut __svector_table_relocated;
+    let len = (&raw const __evector_table_relocated as usize - start as usize) / 4;
+    if len == 0 {
+        return Err(RelocateError::NotReserved);
+    }
+    // Safety: the linker reserves the table for the runtime.
+    unsafe { relocate(start, len) }
+}
+
+/// Point VTOR at `table`.
+///
+/// Use this to switch between vector tables, like a table in flash and a table in RAM.
+/// Use [`restore_vector_table`] to activate the runtime's vector table.
+///
+/// # Safety
+///
+/// `table` must be a valid vector table for this device, and it must stay valid while
+/// it's active. It must be aligned as described by [`RelocateError::Misaligned`].
+pub unsafe fn set_vector_table(table: *const u32) {
+    interrupt_free(|| {
+        // Safety: caller guarantees that the table is valid. The barriers make sure that
+        // the table is written before it's active, and that the next exception entry
+        // uses the new table.
+        unsafe {
+            asm!("dsb", options(nostack, preserves_flags));
+            VTOR.write_volatile(table as u32);
+            asm!("dsb", "isb", options(nostack, preserves_flags));
+        }
+    })
+}
+
+/// Point VTOR at the runtime's vector table.
+pub fn restore_vector_table() {
+    // Safety: the runtime's vector table is always valid.
+    unsafe { set_vector_table(runtime_vector_table()) }
+}
+
+/// Copy the active vector table into `len` words at `dst`, then activate `dst`.
+///
+/// # Safety
+///
+/// `len` words at `dst` are valid for writes, and they're only accessed with
+/// interrupts masked.
+unsafe fn relocate(dst: *mut u32, len: usize) -> Result<(), RelocateError> {
+    let entries = 16 + interrupt_count();
+    if len < entries {
+        return Err(RelocateError::TooSmall);
+    }
+    let align = (entries * 4).next_power_of_two().max(1024);
+    if !(dst as usize).is_multiple_of(align) {
+        return Err(RelocateError::Misaligned);
+    }
+    interrupt_free(|| {
+        let src = active_vector_table();
+        for idx in 0..entries {
+            // Safety: the active table has at least as many entries as the runtime's
+            // table. The caller guarantees that the destination is valid.
+            unsafe { dst.add(idx).write_volatile(src.add(idx).read_volatile()) };
+        }
+        // Safety: the table is a copy of the active table.
+        unsafe { set_vector_table(dst) };
+    });
+    Ok(())
+}
@@ -12,9 +12,14 @@ type Result<T> = std::result::Result<T, Box<dyn std::error::Error>>;
 
 /// Build an example with optional environment variables, returning a path to the ELF.
 fn cargo_build_with_envs(board: &str, envs: &[(&str, &str)]) -> Result<PathBuf> {
+    cargo_build_example("blink-rtic", board, envs)
+}
+
+/// Build the named example with optional environment variables, returning a path to the ELF.
+fn cargo_build_example(example: &str, board: &str, envs: &[(&str, &str)]) -> Result<PathBuf> {
     let status = Command::new("cargo")
         .arg("build")
-        .arg("--example=blink-rtic")
+        .arg(format!("--example={example}"))
         .arg(format!("--features=board/{board},board/rtic"))
         .arg("--target=thumbv7em-none-eabihf")
         .arg(format!("--target-dir=target/{board}"))
@@ -31,7 +36,7 @@ fn cargo_build_with_envs(board: &str, envs: &[(&str, &str)]) -> Result<PathBuf>
     }
 
     let path = PathBuf::from(format!(
-        "target/{board}/thumbv7em-none-eabihf/debug/examples/blink-rtic"
+        "target/{board}/thumbv7em-none-eabihf/debug/examples/{example}"
     ));
     Ok(path)
 }
@@ -114,6 +119,26 @@ impl<'a> ImxrtBinary<'a> {
         u32::from_le_bytes(self.contents[offset..offset + 4].try_into().unwrap())
     }
 
+    /// Read the words between two symbols.
+    ///
+    /// The symbols must be in a section that has contents in the ELF.
+    fn words_between(&self, start: &str, end: &str) -> Vec<u32> {
+        let start = self.symbol_value(start).unwrap();
+        let end = self.symbol_value(end).unwrap();
+        let sec = self
+            .elf
+            .section_headers
+            .iter()
+            .filter(|sec| goblin::elf::section_header::SHT_PROGBITS == sec.sh_type)
+            .find(|sec| sec.sh_addr <= start && end <= sec.sh_addr + sec.sh_size)
+            .expect("Symbols are not in a section with contents");
+        let offset = (sec.sh_offset + start - sec.sh_addr) as usize;
+        (0..(end - start) as usize)
+            .step_by(4)
+            .map(|idx| self.read_u32(offset + idx))
+            .collect()
+    }
+
     fn ivt(&self) -> Result<Ivt> {
         let ivt_at_runtime = self
             .symbol_value("__ivt")
@@ -230,6 +255,14 @@ fn imxrt1010evk() {
         binary.fcb().unwrap()
     );
     assert_eq!(binary.flexram_config().unwrap(), 0b11_10_0101);
+    assert_eq!(binary.symbol_value("__tcm_size_mask"), Some(0));
+    assert!(
+        binary.section(".flexspi_ahb").is_err(),
+        "AHB routine is discarded"
+    );
+    assert_eq!(binary.symbol_value("__flexspi_address"), Some(0));
+    assert_eq!(binary.symbol_value("__reset_reason_size"), Some(0));
+    assert_eq!(binary.symbol_value("__src_srsr"), Some(0));
 
     let ivt = binary.ivt().unwrap();
     assert_eq!(ivt.magic_header, 0x402000D1);
@@ -271,14 +304,14 @@ fn imxrt1010evk() {
     assert_eq!(text.address, ITCM, "text");
     assert_eq!(
         binary.section_lma(".text"),
-        aligned(0x6000_2000 + vector_table.size + xip.size, 4),
+        aligned(0x6000_2000 + vector_table.size + xip.size, 32),
         "text VMA expected behind vector table"
     );
 
     let rodata = binary.section(".rodata").unwrap();
     assert_eq!(
         rodata.address,
-        aligned(0x6000_2000 + vector_table.size + text.size + xip.size, 4),
+        aligned(0x6000_2000 + vector_table.size + text.size + xip.size, 32),
         "rodata LMA & VMA expected behind text"
     );
     assert_eq!(rodata.address, binary.section_lma(".rodata"));
@@ -286,8 +319,8 @@ fn imxrt1010evk() {
     let data = binary.section(".data").unwrap();
     assert_eq!(data.address, 0x2020_0000, "data VMA in OCRAM");
     assert_eq!(
-        data.size, 4,
-        "blink-rtic expected to have a single static mut u32"
+        data.size, 32,
+        "blink-rtic expected to have a single static mut u32, padded to 32 bytes"
     );
     assert_eq!(
         binary.section_lma(".data"),
@@ -303,6 +336,33 @@ fn imxrt1010evk() {
     );
     assert_eq!(binary.section_lma(".bss"), bss.address, "bss is NOLOAD");
 
+    // The runtime initializes .data and .bss before calling static constructors.
+    assert_eq!(
+        binary.symbol_value("__ebss_runtime"),
+        Some(bss.address + aligned(bss.size, 4))
+    );
+    assert_eq!(
+        binary.symbol_value("__ebss"),
+        binary.symbol_value("__sbss"),
+        "cortex-m-rt zeroes nothing"
+    );
+    assert_eq!(
+        binary.symbol_value("__sidata_runtime"),
+        Some(binary.section_lma(".data"))
+    );
+    assert_eq!(
+        binary.symbol_value("__sidata"),
+        Some(data.address),
+        "cortex-m-rt copies .data onto itself"
+    );
+    assert_eq!(binary.symbol_value("__init_statics"), Some(1));
+    assert_eq!(binary.symbol_value("__call_init_arrays"), Some(1));
+    assert_eq!(
+        binary.symbol_value("__init_array_start"),
+        binary.symbol_value("__init_array_end"),
+        "no static constructors"
+    );
+
     let uninit = binary.section(".uninit").unwrap();
     assert_eq!(
         uninit.address,
@@ -314,15 +374,70 @@ fn imxrt1010evk() {
         uninit.address,
         "uninit is NOLOAD"
     );
+    assert_eq!(binary.symbol_value("__boot_timings_size"), Some(52));
+    let boot_timings = binary.symbol_value("__boot_timings").unwrap();
+    assert_eq!(
+        boot_timings + 52,
+        uninit.address + uninit.size,
+        "boot timings at the end of uninit"
+    );
+    assert_eq!(
+        binary.symbol_value("__imxrt_user_pre_init"),
+        binary.symbol_value("__imxrt_rt_user_pre_init"),
+        "default user pre-init hook"
+    );
 
-    let heap = binary.section(".heap").unwrap();
+    let integrity_table = binary.symbol_value("__sintegrity_table").unwrap();
+    assert_eq!(
+        binary.symbol_value("__eintegrity_table"),
+        Some(integrity_table + 16 + 4 * 16),
+        "integrity table has a header and four entries"
+    );
+    assert_eq!(
+        binary.symbol_value("__integrity_crc_table"),
+        Some(integrity_table + 16 + 4 * 16),
+        "CRC table follows the integrity table"
+    );
+
+    assert_eq!(binary.symbol_value("__interrupt_count"), Some(240));
+    let reserved = binary.section(".vector_table_reserved").unwrap();
     assert_eq!(
         Section {
             address: vector_table.address + vector_table.size,
+            size: (240 - IMXRT1010_INTERRUPTS) * 4
+        },
+        reserved,
+        "reserved interrupts behind the vector table"
+    );
+    assert_eq!(
+        binary.section_lma(".vector_table_reserved"),
+        reserved.address,
+        "reserved interrupts are NOLOAD"
+    );
+
+    let relocated = binary.section(".vector_table_relocated").unwrap();
+    assert_eq!(
+        Section {
+            address: aligned(reserved.address + reserved.size, 1024),
+            size: (16 + 240) * 4
+        },
+        relocated,
+        "relocated vector table in DTCM behind reserved interrupts"
+    );
+    assert_eq!(
+        binary.section_lma(".vector_table_relocated"),
+        relocated.address,
+        "relocated vector table is NOLOAD"
+    );
+
+    let heap = binary.section(".heap").unwrap();
+    assert_eq!(
+        Section {
+            address: relocated.address + relocated.size,
             size: 1024
         },
         heap,
-        "1 KiB heap in DTCM behind vector table"
+        "1 KiB heap in DTCM behind relocated vector table"
     );
     assert_eq!(heap.size, 1024);
     assert_eq!(binary.section_lma(".heap"), heap.address, "Heap is NOLOAD");
@@ -376,10 +491,10 @@ fn imxrt1010evk_ram() {
 
     let xip = binary.section(".xip").unwrap();
     let text = binary.section(".text").unwrap();
-    assert_eq!(text.address, aligned(ITCM + xip.size, 4), "text");
+    assert_eq!(text.address, aligned(ITCM + xip.size, 32), "text");
     assert_eq!(
         binary.section_lma(".text"),
-        aligned(ITCM + xip.size, 4),
+        aligned(ITCM + xip.size, 32),
         "text LMA == VMA for RAM boot"
     );
 
@@ -398,8 +513,8 @@ fn imxrt1010evk_ram() {
         "data VMA in OCRAM behind rodata"
     );
     assert_eq!(
-        data.size, 4,
-        "blink-rtic expected to have a single static mut u32"
+        data.size, 32,
+        "blink-rtic expected to have a single static mut u32, padded to 32 bytes"
     );
     assert_eq!(
         binary.section_lma(".data"),
@@ -453,6 +568,9 @@ fn baseline_teensy4(binary: &ImxrtBinary, dcd_at_runtime: u32, stack_size: u64,
         binary.flexram_config().unwrap(),
         0b11111111_101010101010101010101010
     );
+    // 384 KiB DTCM rounds up to 512 KiB, 128 KiB ITCM.
+    assert_eq!(binary.symbol_value("__tcm_size_mask"), Some(0x00FF_0000));
+    assert_eq!(binary.symbol_value("__tcm_size_config"), Some(0x00A8_0000));
 
     let ivt = binary.ivt().unwrap();
     assert_eq!(ivt.magic_header, 0x402000D1);
@@ -489,23 +607,46 @@ fn baseline_teensy4(binary: &ImxrtBinary, dcd_at_runtime: u32, stack_size: u64,
         "vector table is not 1024-byte aligned"
     );
     assert_eq!(binary.section_lma(".vector_table"), 0x6000_2000);
+    assert!(binary.section(".vector_table_relocated").is_err());
+    assert_eq!(binary.symbol_value("__svector_table_relocated"), Some(0));
 
-    let text = binary.section(".text").unwrap();
-    let expected_text_address = aligned(
-        binary.section_lma(".vector_table") + vector_table.size + xip.size,
-        4,
+    let flexspi_ahb = binary.section(".flexspi_ahb").unwrap();
+    assert!(flexspi_ahb.size > 0, "AHB routine is missing");
+    assert!(
+        ITCM <= flexspi_ahb.address && flexspi_ahb.address < 0x0008_0000,
+        "AHB routine does not run from ITCM"
+    );
+    assert_eq!(
+        binary.section_lma(".flexspi_ahb"),
+        aligned(0x6000_2000 + vector_table.size + xip.size, 4),
+        "AHB routine LMA expected behind the vector table"
+    );
+    assert_eq!(
+        binary.symbol_value("__sflexspi_ahb"),
+        Some(flexspi_ahb.address)
+    );
+    assert_eq!(binary.symbol_value("__flexspi_address"), Some(0x402A_8000));
+    assert_eq!(binary.symbol_value("__flexspi_ahbcr_prefetch"), Some(0x20));
+    assert_eq!(binary.symbol_value("__flexspi_ahbrxbuf_count"), Some(4));
+    assert_eq!(
+        binary.symbol_value("__flexspi_ahbrxbuf0cr0"),
+        Some(0x8000_0080)
     );
+    assert_eq!(binary.symbol_value("__flexspi_ahbrxbuf1cr0"), Some(0));
+
+    let text = binary.section(".text").unwrap();
+    let expected_text_address = aligned(binary.section_lma(".flexspi_ahb") + flexspi_ahb.size, 32);
     assert_eq!(text.address, expected_text_address, "text");
     assert_eq!(
         binary.section_lma(".text"),
-        aligned(0x6000_2000 + vector_table.size + xip.size, 4),
-        "text VMA expected behind vector table"
+        expected_text_address,
+        "text VMA expected behind the AHB routine"
     );
 
     let rodata = binary.section(".rodata").unwrap();
     assert_eq!(
         rodata.address,
-        vector_table.address + vector_table.size,
+        aligned(vector_table.address + vector_table.size, 32),
         "rodata LMA & VMA expected behind text"
     );
     assert!(binary.section_lma(".rodata") >= binary.section_lma(".text") + aligned(text.size, 4));
@@ -517,8 +658,8 @@ fn baseline_teensy4(binary: &ImxrtBinary, dcd_at_runtime: u32, stack_size: u64,
         "data VMA in DTCM behind rodata"
     );
     assert_eq!(
-        data.size, 4,
-        "blink-rtic expected to have a single static mut u32"
+        data.size, 32,
+        "blink-rtic expected to have a single static mut u32, padded to 32 bytes"
     );
     assert_eq!(
         binary.section_lma(".data"),
@@ -534,6 +675,18 @@ fn baseline_teensy4(binary: &ImxrtBinary, dcd_at_runtime: u32, stack_size: u64,
     );
     assert_eq!(binary.section_lma(".bss"), bss.address, "bss is NOLOAD");
 
+    // The runtime copies and zeroes these sections in 32-byte bursts.
+    for name in [".text", ".rodata", ".data", ".bss"] {
+        let section = binary.section(name).unwrap();
+        for address in [
+            section.address,
+            section.address + section.size,
+            binary.section_lma(name),
+        ] {
+            assert!(address.is_multiple_of(32), "{name} at {address:#X}");
+        }
+    }
+
     let uninit = binary.section(".uninit").unwrap();
     assert_eq!(
         uninit.address,
@@ -545,6 +698,27 @@ fn baseline_teensy4(binary: &ImxrtBinary, dcd_at_runtime: u32, stack_size: u64,
         uninit.address,
         "uninit is NOLOAD"
     );
+    assert_eq!(binary.symbol_value("__src_srsr"), Some(0x400F_8008));
+    assert_eq!(binary.symbol_value("__init_statics"), Some(0));
+    assert_eq!(binary.symbol_value("__call_init_arrays"), Some(0));
+    assert_eq!(binary.symbol_value("__crash_record_size"), Some(0));
+    assert_eq!(binary.symbol_value("__crash_stack_size"), Some(0));
+    assert_eq!(
+        binary.symbol_value("HardFault"),
+        binary.symbol_value("HardFault_"),
+        "default hard fault handler"
+    );
+    assert_eq!(binary.symbol_value("__imxrt_rt_v0.2"), Some(0x1060));
+    assert_eq!(
+        binary.symbol_value("__imxrt_rt_placements"),
+        Some(0x000A_AAA0),
+        "flash text, and DTCM for the rest"
+    );
+    assert_eq!(
+        binary.symbol_value("__reset_reason"),
+        Some(uninit.address + uninit.size - 4),
+        "reset reason at the end of uninit"
+    );
 
     let heap = binary.section(".heap").unwrap();
     assert_eq!(
@@ -651,6 +825,218 @@ fn teensy4_env_override_fail() {
         .expect("Build should fail since BOARD_STACK can't be parsed");
 }
 
+#[test]
+#[ignore = "building an example can take time"]
+fn teensy4_layout() {
+    // blink-rtic calls an XiP function, which prevents spilling. The board's
+    // build script can't pass the spilling option to the example's linker.
+    let path = cargo_build_example(
+        "blink-blocking",
+        "__layout",
+        &[(
+            "CARGO_TARGET_THUMBV7EM_NONE_EABIHF_RUSTFLAGS",
+            "-C link-arg=--enable-non-contiguous-regions",
+        )],
+    )
+    .expect("Unable to build example");
+    let contents = fs::read(path).expect("Could not read ELF file");
+    let elf = Elf::parse(&contents).expect("Could not parse ELF");
+
+    let binary = ImxrtBinary::new(&elf, &contents);
+    assert_eq!(
+        binary.flexram_config().unwrap(),
+        0b11_10101010_0101010101010101010101
+    );
+
+    let text = binary.section(".text").unwrap();
+    assert_eq!(text.address, ITCM, "text");
+    assert!(text.address + text.size <= 32 * 1024, "text fits in ITCM");
+    let text_spill = binary.section(".text_spill").unwrap();
+    assert!(text_spill.size > 0, "text doesn't spill");
+    assert!(
+        0x6000_0000 <= text_spill.address && text_spill.address < 0x7000_0000,
+        "spilled text is not XiP"
+    );
+    assert_eq!(binary.section_lma(".text_spill"), text_spill.address);
+
+    let placed = binary.section(".placed_input_0").unwrap();
+    assert_eq!(placed.address, 0x2020_0000, "board rodata VMA in OCRAM");
+    assert!(placed.size > 0);
+    assert!(
+        placed.size.is_multiple_of(64),
+        "board rodata end is 64-byte aligned"
+    );
+    assert_eq!(
+        binary.section_lma(".placed_input_0"),
+        aligned(
+            binary.section_lma(".xip") + binary.section(".xip").unwrap().size,
+            64
+        ),
+        "board rodata LMA behind XiP"
+    );
+    let rodata = binary.section(".rodata").unwrap();
+    assert_eq!(
+        rodata.address,
+        placed.address + placed.size,
+        "rodata behind board rodata"
+    );
+
+    let mailbox = binary.section(".mailbox").unwrap();
+    assert_eq!(mailbox.address, 0x2001_F000);
+    assert_eq!(binary.section_lma(".mailbox"), mailbox.address, "NOLOAD");
+    let shared = binary.section(".shared").unwrap();
+    assert_eq!(shared.address, 0x2001_E000);
+    let shared_lma = binary.section_lma(".shared");
+    assert!(
+        (0x6000_0000..0x7000_0000).contains(&shared_lma),
+        "shared section loads from flash"
+    );
+    assert_eq!(
+        binary.words_between("__scopy_table", "__ecopy_table"),
+        [
+            shared.address as u32,
+            (shared.address + shared.size) as u32,
+            shared_lma as u32
+        ],
+        "only the shared section is copied"
+    );
+
+    assert_eq!(binary.symbol_value("__skip_text_copy"), Some(1));
+    assert_eq!(binary.symbol_value("__skip_rodata_copy"), Some(0));
+    let deferred = binary.words_between("__sdeferred_table", "__edeferred_table");
+    let [text_entry, spill_entry, placed_entry] = deferred.as_chunks::<4>().0 else {
+        panic!("Unexpected deferred table {deferred:#X?}");
+    };
+    // FNV-1a of ".text".
+    assert_eq!(
+        text_entry,
+        &[
+            0x88A8_2EC2,
+            text.address as u32,
+            (text.address + text.size) as u32,
+            binary.section_lma(".text") as u32
+        ]
+    );
+    assert_eq!(
+        spill_entry,
+        &[
+            0x88A8_2EC2,
+            text_spill.address as u32,
+            (text_spill.address + text_spill.size) as u32,
+            text_spill.address as u32
+        ]
+    );
+    assert_ne!(placed_entry[0], 0x88A8_2EC2);
+    assert_eq!(
+        placed_entry[1..],
+        [
+            placed.address as u32,
+            (placed.address + placed.size) as u32,
+            binary.section_lma(".placed_input_0") as u32
+        ]
+    );
+    // The loader executes in place, so it works before the deferred sections are loaded.
+    let loader = binary.symbol_value("__imxrt_rt_load_deferred").unwrap() & !1;
+    let xip = binary.section(".xip").unwrap();
+    assert!((xip.address..xip.address + xip.size).contains(&loader));
+    for section in [&text, &text_spill, &placed] {
+        assert!(!(section.address..section.address + section.size).contains(&loader));
+    }
+
+    // To measure it, the runtime initializes statics itself, without calling constructors.
+    assert_eq!(binary.symbol_value("__boot_timings_size"), Some(52));
+    assert_eq!(binary.symbol_value("__init_statics"), Some(1));
+    assert_eq!(binary.symbol_value("__call_init_arrays"), Some(0));
+    assert_eq!(
+        binary.symbol_value("__ebss"),
+        binary.symbol_value("__sbss"),
+        "cortex-m-rt zeroes nothing"
+    );
+
+    // The runtime doesn't check deferred sections.
+    let vector_table = binary.section(".vector_table").unwrap();
+    assert_eq!(
+        binary.words_between("__sintegrity_table", "__eintegrity_table"),
+        [
+            0x4352_4332,
+            binary.symbol_value("__sintegrity_table").unwrap() as u32,
+            0,
+            2,
+            vector_table.address as u32,
+            (vector_table.address + vector_table.size) as u32,
+            binary.section_lma(".vector_table") as u32,
+            0,
+            rodata.address as u32,
+            (rodata.address + rodata.size) as u32,
+            binary.section_lma(".rodata") as u32,
+            0
+        ]
+    );
+
+    assert_eq!(binary.symbol_value("__wdog1_config"), Some(0x334));
+    assert_eq!(binary.symbol_value("__wdog2_config"), Some(0x1));
+    assert_eq!(binary.symbol_value("__rtwdog_config"), Some(0x31A0));
+    assert_eq!(binary.symbol_value("__rtwdog_toval"), Some(0x500));
+
+    assert_eq!(
+        binary.symbol_value("__imxrt_rt_placements"),
+        Some(0x000A_BEF1),
+        "ITCM text that spills to flash, OCRAM data, and DTCM for the rest"
+    );
+
+    assert_eq!(binary.symbol_value("__arm_pll_div_select"), Some(100));
+    assert_eq!(binary.symbol_value("__arm_podf"), Some(1));
+    assert_eq!(binary.symbol_value("__ipg_podf"), Some(3));
+    assert_eq!(binary.symbol_value("__dcdc_trg"), Some(0x12));
+}
+
+#[test]
+#[ignore = "building an example can take time"]
+fn teensy4_compress() {
+    let path = cargo_build("__compress").expect("Unable to build example");
+    let contents = fs::read(path).expect("Could not read ELF file");
+    let elf = Elf::parse(&contents).expect("Could not parse ELF");
+
+    let binary = ImxrtBinary::new(&elf, &contents);
+    let table = binary.words_between("__scompression_table", "__ecompression_table");
+    let (header, entries) = table.split_at(5);
+    assert_eq!(
+        header,
+        [
+            0x5A4C_5249,
+            binary.symbol_value("__scompression_table").unwrap() as u32,
+            0,
+            0x601F_0000,
+            4
+        ]
+    );
+    let (entries, []) = entries.as_chunks::<4>() else {
+        panic!("Unexpected compression table {table:#X?}");
+    };
+    for (entry, name) in entries
+        .iter()
+        .zip([".text", ".text_spill", ".rodata", ".data"])
+    {
+        let section = binary.section(name).unwrap();
+        assert_eq!(
+            entry[..2],
+            [
+                section.address as u32,
+                (section.address + section.size) as u32,
+            ],
+            "{name}"
+        );
+        assert_eq!(entry[3], section.size as u32, "{name}");
+        // An empty section isn't in any loadable segment.
+        if section.size > 0 {
+            assert_eq!(entry[2], binary.section_lma(name) as u32, "{name}");
+        }
+    }
+    assert_eq!(binary.symbol_value("__skip_text_copy"), Some(1));
+    assert_eq!(binary.symbol_value("__skip_rodata_copy"), Some(1));
+    assert_eq!(binary.symbol_value("__data_compressed"), Some(1));
+}
+
 #[test]
 #[ignore = "building an example can take time"]
 fn imxrt1170evk_cm7() {
@@ -709,19 +1095,68 @@ fn imxrt1170evk_cm7() {
     assert_eq!(binary.section_lma(".vector_table"), 0x3000_2000);
 
     let xip = binary.section(".xip").unwrap();
+    let extab_start = binary.symbol_value("__extab_start").unwrap();
+    assert_eq!(
+        extab_start,
+        aligned(0x3000_2000 + vector_table.size + xip.size, 4),
+        "unwind tables expected in flash, behind the vector table"
+    );
+    let exidx_start = binary.symbol_value("__exidx_start").unwrap();
+    let exidx_end = binary.symbol_value("__exidx_end").unwrap();
+    assert_eq!(
+        exidx_start,
+        aligned(binary.symbol_value("__extab_end").unwrap(), 4)
+    );
+    assert!(
+        exidx_end > exidx_start && (exidx_end - exidx_start).is_multiple_of(8),
+        "index has at least the linker's sentinel entry"
+    );
+    assert_eq!(binary.section_lma(".ARM.exidx"), exidx_start, "not copied");
+
     let text = binary.section(".text").unwrap();
     assert_eq!(text.address, ITCM, "text");
     assert_eq!(
         binary.section_lma(".text"),
-        aligned(0x3000_2000 + vector_table.size + xip.size, 4),
-        "text VMA expected behind vector table"
+        aligned(exidx_end, 32),
+        "text VMA expected behind the unwind tables"
+    );
+
+    let retained = binary.section(".retained").unwrap();
+    assert_eq!(
+        Section {
+            address: vector_table.address + vector_table.size,
+            size: 0xC4 + 256,
+        },
+        retained,
+        "crash record and 256 byte retained section in DTCM behind vector table"
+    );
+    assert_eq!(
+        binary.symbol_value("__crash_record"),
+        Some(retained.address),
+        "crash record at the start of the retained section"
+    );
+    assert_eq!(binary.symbol_value("__crash_record_size"), Some(0xC4));
+    assert_eq!(
+        binary.symbol_value("__sretained"),
+        Some(retained.address + 0xC4),
+        "retained data behind the crash record"
+    );
+    assert_eq!(
+        binary.symbol_value("HardFault"),
+        binary.symbol_value("__imxrt_rt_crash_hard_fault"),
+        "runtime provides the HardFault handler"
+    );
+    assert_eq!(
+        binary.section_lma(".retained"),
+        retained.address,
+        "retained is NOLOAD"
     );
 
     let rodata = binary.section(".rodata").unwrap();
     assert_eq!(
         rodata.address,
-        vector_table.address + vector_table.size,
-        "rodata moved to DTCM behind vector table"
+        aligned(retained.address + retained.size, 32),
+        "rodata moved to DTCM behind retained section"
     );
     assert!(
         binary.section_lma(".rodata") >= 0x3000_2000 + vector_table.size + aligned(text.size, 4),
@@ -730,8 +1165,8 @@ fn imxrt1170evk_cm7() {
     let data = binary.section(".data").unwrap();
     assert_eq!(data.address, 0x2024_0000, "data VMA in OCRAM");
     assert_eq!(
-        data.size, 4,
-        "blink-rtic expected to have a single static mut u32"
+        data.size, 32,
+        "blink-rtic expected to have a single static mut u32, padded to 32 bytes"
     );
     assert_eq!(
         binary.section_lma(".data"),
@@ -742,22 +1177,44 @@ fn imxrt1170evk_cm7() {
     let bss = binary.section(".bss").unwrap();
     assert_eq!(
         bss.address,
-        data.address + aligned(data.size, 4),
-        "bss in OCRAM behind data"
+        aligned(data.address + aligned(data.size, 4), 32),
+        "bss in OCRAM behind data, 32-byte aligned"
     );
+    assert!(bss.size.is_multiple_of(32), "bss end is 32-byte aligned");
     assert_eq!(binary.section_lma(".bss"), bss.address, "bss is NOLOAD");
 
     let uninit = binary.section(".uninit").unwrap();
     assert_eq!(
         uninit.address,
-        bss.address + aligned(bss.size, 4),
+        bss.address + bss.size,
         "uninit in OCRAM behind bss"
     );
+    assert!(
+        uninit.size.is_multiple_of(32),
+        "uninit end is 32-byte aligned"
+    );
     assert_eq!(
         binary.section_lma(".uninit"),
         uninit.address,
         "uninit is NOLOAD"
     );
+    assert_eq!(binary.symbol_value("__src_srsr"), Some(0x40C0_4010));
+    let reset_reason = binary.symbol_value("__reset_reason").unwrap();
+    assert!(
+        (uninit.address..uninit.address + uninit.size).contains(&reset_reason),
+        "reset reason in uninit"
+    );
+    assert_eq!(binary.symbol_value("__crash_stack_size"), Some(0x800));
+    let crash_stack = binary.symbol_value("__crash_stack").unwrap();
+    assert_eq!(
+        crash_stack % 8,
+        0,
+        "crash record handler's stack is aligned"
+    );
+    assert!(
+        crash_stack >= uninit.address + 0x800 && crash_stack <= uninit.address + uninit.size,
+        "crash record handler's stack in uninit"
+    );
 
     let heap = binary.section(".heap").unwrap();
     assert_eq!(
@@ -844,19 +1301,56 @@ fn imxrt1170evk_cm7_nonboot() {
         "text VMA expected behind vector table"
     );
 
+    assert_eq!(
+        binary.symbol_value("__extab_start"),
+        Some(0x3000_0000 + IMAGE_OFFSET + aligned(xip.size, 4) + vector_table.size),
+        "unwind tables expected behind xip"
+    );
+
     let text = binary.section(".text").unwrap();
     assert_eq!(text.address, ITCM, "text");
     assert_eq!(
         binary.section_lma(".text"),
-        0x3000_0000 + IMAGE_OFFSET + aligned(xip.size, 4) + vector_table.size,
-        "text VMA expected behind vector table"
+        aligned(binary.symbol_value("__exidx_end").unwrap(), 32),
+        "text VMA expected behind the unwind tables"
+    );
+
+    let retained = binary.section(".retained").unwrap();
+    assert_eq!(
+        Section {
+            address: vector_table.address + vector_table.size,
+            size: 0xC4 + 256,
+        },
+        retained,
+        "crash record and 256 byte retained section in DTCM behind vector table"
+    );
+    assert_eq!(
+        binary.symbol_value("__crash_record"),
+        Some(retained.address),
+        "crash record at the start of the retained section"
+    );
+    assert_eq!(binary.symbol_value("__crash_record_size"), Some(0xC4));
+    assert_eq!(
+        binary.symbol_value("__sretained"),
+        Some(retained.address + 0xC4),
+        "retained data behind the crash record"
+    );
+    assert_eq!(
+        binary.symbol_value("HardFault"),
+        binary.symbol_value("__imxrt_rt_crash_hard_fault"),
+        "runtime provides the HardFault handler"
+    );
+    assert_eq!(
+        binary.section_lma(".retained"),
+        retained.address,
+        "retained is NOLOAD"
     );
 
     let rodata = binary.section(".rodata").unwrap();
     assert_eq!(
         rodata.address,
-        vector_table.address + vector_table.size,
-        "rodata moved to DTCM behind vector table"
+        aligned(retained.address + retained.size, 32),
+        "rodata moved to DTCM behind retained section"
     );
     assert!(
         binary.section_lma(".rodata") >= 0x3000_2000 + vector_table.size + aligned(text.size, 4),
@@ -865,8 +1359,8 @@ fn imxrt1170evk_cm7_nonboot() {
     let data = binary.section(".data").unwrap();
     assert_eq!(data.address, 0x2024_0000, "data VMA in OCRAM");
     assert_eq!(
-        data.size, 4,
-        "blink-rtic expected to have a single static mut u32"
+        data.size, 32,
+        "blink-rtic expected to have a single static mut u32, padded to 32 bytes"
     );
     assert_eq!(
         binary.section_lma(".data"),
@@ -877,22 +1371,44 @@ fn imxrt1170evk_cm7_nonboot() {
     let bss = binary.section(".bss").unwrap();
     assert_eq!(
         bss.address,
-        data.address + aligned(data.size, 4),
-        "bss in OCRAM behind data"
+        aligned(data.address + aligned(data.size, 4), 32),
+        "bss in OCRAM behind data, 32-byte aligned"
     );
+    assert!(bss.size.is_multiple_of(32), "bss end is 32-byte aligned");
     assert_eq!(binary.section_lma(".bss"), bss.address, "bss is NOLOAD");
 
     let uninit = binary.section(".uninit").unwrap();
     assert_eq!(
         uninit.address,
-        bss.address + aligned(bss.size, 4),
+        bss.address + bss.size,
         "uninit in OCRAM behind bss"
     );
+    assert!(
+        uninit.size.is_multiple_of(32),
+        "uninit end is 32-byte aligned"
+    );
     assert_eq!(
         binary.section_lma(".uninit"),
         uninit.address,
         "uninit is NOLOAD"
     );
+    assert_eq!(binary.symbol_value("__src_srsr"), Some(0x40C0_4010));
+    let reset_reason = binary.symbol_value("__reset_reason").unwrap();
+    assert!(
+        (uninit.address..uninit.address + uninit.size).contains(&reset_reason),
+        "reset reason in uninit"
+    );
+    assert_eq!(binary.symbol_value("__crash_stack_size"), Some(0x800));
+    let crash_stack = binary.symbol_value("__crash_stack").unwrap();
+    assert_eq!(
+        crash_stack % 8,
+        0,
+        "crash record handler's stack is aligned"
+    );
+    assert!(
+        crash_stack >= uninit.address + 0x800 && crash_stack <= uninit.address + uninit.size,
+        "crash record handler's stack in uninit"
+    );
 
     let heap = binary.section(".heap").unwrap();
     assert_eq!(